@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::Path;
+use clap::Parser;
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use rskill::{
+    utils,
+    cli::Cli,
+    scanner::{ProjectScanner, ScanStats},
+};
+use tempfile::TempDir;
+
+/// shape of the synthetic tree, roughly a busy `~/projects` directory
+const PROJECTS: usize = 200;
+const FILES_PER_TARGET: usize = 50;
+const FILE_SIZE: usize = 4096;
+
+fn synthetic_tree() -> TempDir {
+    let root = TempDir::new().expect("create temp dir");
+    let content = vec![0u8; FILE_SIZE];
+
+    for i in 0..PROJECTS {
+        let project = root.path().join(format!("group-{}", i % 10)).join(format!("project-{}", i));
+        fs::create_dir_all(project.join("src")).unwrap();
+        fs::write(
+            project.join("Cargo.toml"),
+            format!("[package]\nname = \"project-{}\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n", i),
+        )
+        .unwrap();
+        fs::write(project.join("src/main.rs"), "fn main() {}\n").unwrap();
+
+        let deps = project.join("target/debug/deps");
+        fs::create_dir_all(&deps).unwrap();
+        for file in 0..FILES_PER_TARGET {
+            fs::write(deps.join(format!("lib{}.rlib", file)), &content).unwrap();
+        }
+    }
+
+    root
+}
+
+fn cli_for(path: &Path) -> Cli {
+    Cli::parse_from(["rskill", "-d", path.to_str().unwrap()])
+}
+
+fn bench_find_rust_projects(c: &mut Criterion) {
+    let tree = synthetic_tree();
+    let cli = cli_for(tree.path());
+    let excluded_dirs = cli.get_excluded_dirs();
+
+    c.bench_function("find_rust_projects", |b| {
+        b.iter(|| {
+            ProjectScanner::find_rust_projects(tree.path(), &excluded_dirs, &cli, &mut ScanStats::default(), None)
+                .unwrap()
+        })
+    });
+}
+
+fn bench_calculate_dir_size(c: &mut Criterion) {
+    let tree = synthetic_tree();
+
+    c.bench_function("calculate_dir_size", |b| {
+        b.iter(|| utils::calculate_dir_size(tree.path(), false).unwrap())
+    });
+}
+
+fn bench_sort_projects(c: &mut Criterion) {
+    let tree = synthetic_tree();
+    let cli = cli_for(tree.path());
+    let scanner = ProjectScanner::new(cli.clone());
+    let projects =
+        ProjectScanner::find_rust_projects(tree.path(), &cli.get_excluded_dirs(), &cli, &mut ScanStats::default(), None)
+            .unwrap();
+
+    c.bench_function("sort_projects", |b| {
+        b.iter_batched(
+            || projects.clone(),
+            |mut projects| scanner.sort(&mut projects),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+criterion_group!(benches, bench_find_rust_projects, bench_calculate_dir_size, bench_sort_projects);
+criterion_main!(benches);
@@ -0,0 +1,170 @@
+use std::{path::{Path, PathBuf}, time::Instant};
+use crate::error::Result;
+#[cfg(feature = "archives")]
+use std::{collections::HashSet, fs::File, io::{self, Read}};
+
+/// a tar or zip archive holding at least one crate, sized from its entry headers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    pub path: PathBuf,
+    /// uncompressed bytes under the `target/` directories next to a `Cargo.toml`
+    pub target_size: u64,
+    /// uncompressed bytes of every entry
+    pub total_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Tar,
+    TarGz,
+    TarZst,
+    Zip,
+}
+
+impl Format {
+    fn of(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name.ends_with(".tar") {
+            Some(Format::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Format::TarGz)
+        } else if name.ends_with(".tar.zst") || name.ends_with(".tzst") {
+            Some(Format::TarZst)
+        } else if name.ends_with(".zip") {
+            Some(Format::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+pub fn is_archive(path: &Path) -> bool {
+    Format::of(path).is_some()
+}
+
+/// read the entry list of an archive without extracting anything, `None` when it holds
+/// no `Cargo.toml`; compressed tarballs are decompressed as a stream to reach the headers,
+/// which gives up with a `TimedOut` error once the scan's deadline passes
+#[cfg(feature = "archives")]
+pub fn peek(path: &Path, deadline: Option<Instant>) -> Result<Option<ArchiveSummary>> {
+    let Some(format) = Format::of(path) else {
+        return Ok(None);
+    };
+    let file = File::open(path)?;
+    let entries = match format {
+        Format::Tar => tar_entries(file, deadline)?,
+        Format::TarGz => tar_entries(flate2::read::GzDecoder::new(file), deadline)?,
+        Format::TarZst => tar_entries(zstd::Decoder::new(file)?, deadline)?,
+        Format::Zip => zip_entries(file, deadline)?,
+    };
+    Ok(summarize(path, &entries))
+}
+
+/// built without the `archives` feature, nothing can be read
+#[cfg(not(feature = "archives"))]
+pub fn peek(_path: &Path, _deadline: Option<Instant>) -> Result<Option<ArchiveSummary>> {
+    Ok(None)
+}
+
+#[cfg(feature = "archives")]
+fn check_deadline(deadline: Option<Instant>) -> io::Result<()> {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        return Err(io::Error::new(io::ErrorKind::TimedOut, "scan deadline reached"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "archives")]
+fn tar_entries(reader: impl Read, deadline: Option<Instant>) -> io::Result<Vec<(PathBuf, u64)>> {
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for entry in archive.entries()? {
+        check_deadline(deadline)?;
+        let entry = entry?;
+        entries.push((entry.path()?.into_owned(), entry.size()));
+    }
+    Ok(entries)
+}
+
+#[cfg(feature = "archives")]
+fn zip_entries(file: File, deadline: Option<Instant>) -> io::Result<Vec<(PathBuf, u64)>> {
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut entries = Vec::new();
+    for index in 0..archive.len() {
+        check_deadline(deadline)?;
+        // the raw entry only reads the central directory, nothing is decompressed
+        let entry = archive.by_index_raw(index)?;
+        if let Some(path) = entry.enclosed_name() {
+            entries.push((path, entry.size()));
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(feature = "archives")]
+fn summarize(path: &Path, entries: &[(PathBuf, u64)]) -> Option<ArchiveSummary> {
+    let crate_dirs: HashSet<&Path> = entries
+        .iter()
+        .filter(|(entry, _)| entry.file_name().is_some_and(|name| name == "Cargo.toml"))
+        .filter_map(|(entry, _)| entry.parent())
+        .collect();
+    if crate_dirs.is_empty() {
+        return None;
+    }
+
+    let target_size = entries
+        .iter()
+        .filter(|(entry, _)| crate_dirs.iter().any(|dir| entry.starts_with(dir.join("target"))))
+        .map(|(_, size)| size)
+        .sum();
+    Some(ArchiveSummary {
+        path: path.to_path_buf(),
+        target_size,
+        total_size: entries.iter().map(|(_, size)| size).sum(),
+    })
+}
+
+#[cfg(all(test, feature = "archives"))]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn append(builder: &mut tar::Builder<impl io::Write>, path: &str, size: usize) {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(size as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, path, &vec![0u8; size][..]).unwrap();
+    }
+
+    #[test]
+    fn test_peek_tar_zst_counts_target_of_crates_only() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("old.tar.zst");
+        let mut builder = tar::Builder::new(zstd::Encoder::new(File::create(&path).unwrap(), 0).unwrap().auto_finish());
+        append(&mut builder, "old/Cargo.toml", 10);
+        append(&mut builder, "old/src/main.rs", 20);
+        append(&mut builder, "old/target/debug/old", 300);
+        append(&mut builder, "notes/target/plan.txt", 5);
+        builder.finish().unwrap();
+        drop(builder);
+
+        let summary = peek(&path, None).unwrap().unwrap();
+        assert_eq!(summary.target_size, 300);
+        assert_eq!(summary.total_size, 335);
+        assert!(peek(&path, Some(Instant::now())).is_err());
+    }
+
+    #[test]
+    fn test_peek_skips_archives_without_crates() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("photos.tar");
+        let mut builder = tar::Builder::new(File::create(&path).unwrap());
+        append(&mut builder, "photos/target/a.jpg", 10);
+        builder.finish().unwrap();
+        drop(builder);
+
+        assert_eq!(peek(&path, None).unwrap(), None);
+        assert!(!is_archive(Path::new("notes.txt")));
+    }
+}
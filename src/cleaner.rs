@@ -0,0 +1,988 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::time::Instant;
+use anyhow::Result;
+use colored::Colorize;
+use crate::{
+    utils,
+    ExitCode,
+    cli::{AutoConfirm, Cli},
+    error::RskillError,
+    plan::{DeletionPlan, PlanCheck},
+    report::{DeleteReport, ReportError},
+    project::{ArtifactType, BuildArtifact, RustProject, DEFAULT_STALE_DAYS},
+    scanner::ProjectScanner,
+};
+
+/// answer to a `--confirm-each` prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EachAnswer {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+/// non-interactive cleaning of scanned projects
+pub struct BatchCleaner {
+    cli: Cli,
+    /// when the previous deletion finished, for `--throttle`
+    last_deletion: Cell<Option<Instant>>,
+    started: Instant,
+    /// what was deleted and what failed, for `--report-json`
+    report: RefCell<DeleteReport>,
+}
+
+impl BatchCleaner {
+    pub fn new(cli: Cli) -> Self {
+        Self {
+            cli,
+            last_deletion: Cell::new(None),
+            started: Instant::now(),
+            report: RefCell::new(DeleteReport::default()),
+        }
+    }
+
+    pub fn record_scanned(&self, projects_scanned: usize) {
+        self.report.borrow_mut().projects_scanned = projects_scanned;
+    }
+
+    /// with `--report-json`, write what this cleaner did so far
+    pub fn save_report(&self) -> Result<()> {
+        let Some(path) = &self.cli.report_json else {
+            return Ok(());
+        };
+        let mut report = self.report.borrow_mut();
+        report.duration_ms = self.started.elapsed().as_millis() as u64;
+        report.dry_run = self.cli.dry_run;
+        report.save(path)
+    }
+
+    fn record_deleted(&self, project: &Path, size: u64) {
+        self.report.borrow_mut().record_deleted(project, size);
+    }
+
+    pub fn record_error(&self, path: &Path, message: impl ToString) {
+        self.report.borrow_mut().errors.push(ReportError { path: path.to_path_buf(), message: message.to_string() });
+    }
+
+    /// delete the directories of a previously written plan, each one is re-checked first
+    /// and skipped if it grew since the plan was made
+    pub fn apply_plan(&self, plan: &DeletionPlan) -> Result<ExitCode> {
+        let mut reclaimed = 0u64;
+        let mut deleted_count = 0;
+        let mut skipped_count = 0;
+        let mut failed_count = 0;
+
+        for entry in &plan.entries {
+            // an edited or stale plan mustn't reach outside the build output of its project
+            if entry.path.exists()
+                && !entry.is_inside_artifacts(&ProjectScanner::artifact_dirs(&entry.project_path, &self.cli)?)
+            {
+                skipped_count += 1;
+                self.record_error(&entry.path, "not a build directory of its project");
+                eprintln!(
+                    "{} {}: not a build directory of {}",
+                    "Skipped".yellow(),
+                    entry.path.display(),
+                    entry.project_path.display()
+                );
+                continue;
+            }
+
+            match entry.check(self.cli.disk_usage)? {
+                PlanCheck::Missing => {
+                    println!("{} {}", "Already gone".dimmed(), entry.path.display());
+                    continue;
+                }
+                PlanCheck::Grown { now } => {
+                    skipped_count += 1;
+                    self.record_error(&entry.path, "grew since the plan was made");
+                    eprintln!(
+                        "{} {}: grew from {} to {} since the plan was made",
+                        "Skipped".yellow(),
+                        entry.path.display(),
+                        utils::format_size(entry.size, self.cli.gb),
+                        utils::format_size(now, self.cli.gb)
+                    );
+                    continue;
+                }
+                PlanCheck::Ready => {}
+            }
+
+            if utils::build_in_progress(&entry.path) {
+                skipped_count += 1;
+                self.record_error(&entry.path, "a build is in progress");
+                eprintln!("{} {}: a build is in progress", "Skipped".yellow(), entry.path.display());
+                continue;
+            }
+
+            if self.cli.skip_open && utils::open_in_editor(&entry.project_path, Some(&entry.path)) {
+                skipped_count += 1;
+                eprintln!("{} {}: open in an editor", "Skipped".yellow(), entry.path.display());
+                continue;
+            }
+
+            if !self.pre_delete_allows(&entry.project_path, entry.size) {
+                skipped_count += 1;
+                continue;
+            }
+
+            match self.remove_directory(&entry.path) {
+                Ok(()) => {
+                    self.post_delete(&entry.project_path, entry.size);
+                    self.record_deleted(&entry.project_path, entry.size);
+                    deleted_count += 1;
+                    reclaimed += entry.size;
+                    println!(
+                        "{:<30} {}",
+                        entry.project,
+                        utils::format_size(entry.size, self.cli.gb).cyan()
+                    );
+                }
+                Err(err) => {
+                    failed_count += 1;
+                    eprintln!("{} {}", "Error:".red(), err);
+                }
+            }
+        }
+
+        let verb = if self.cli.dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!(
+            "\n{} {} from {} of {} planned directories",
+            verb,
+            utils::format_size(reclaimed, self.cli.gb).bold().green(),
+            deleted_count,
+            plan.entries.len()
+        );
+
+        if skipped_count + failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+
+    /// remove target directories without any files in them, pruned projects are
+    /// updated so a following clean doesn't count them again
+    pub fn prune_empty_targets(&self, projects: &mut [RustProject]) -> Result<ExitCode> {
+        let mut pruned_count = 0;
+        let mut failed_count = 0;
+
+        for project in projects.iter_mut().filter(|p| !p.protected) {
+            let Some(target_dir) = &project.target_dir else {
+                continue;
+            };
+
+            if !utils::is_empty_dir_tree(target_dir) {
+                continue;
+            }
+
+            match self.remove_directory(target_dir) {
+                Ok(()) => {
+                    pruned_count += 1;
+                    self.record_deleted(&project.path, 0);
+                    println!("{} {}", "Pruned empty".dimmed(), target_dir.display());
+                    project.target_dir = None;
+                    project.target_size = 0;
+                    project.size_capped = false;
+                }
+                Err(err) => {
+                    failed_count += 1;
+                    eprintln!("{} {}", "Error:".red(), err);
+                }
+            }
+        }
+
+        let verb = if self.cli.dry_run { "Would prune" } else { "Pruned" };
+        println!("{} {} empty target directories", verb, pruned_count);
+
+        if failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+
+    /// delete every unprotected project's artifacts, with `confirm` the plan is shown first
+    /// and with `--confirm-each` every project is asked about on its own; `--auto-confirm`
+    /// only asks about the projects its policy doesn't cover
+    pub fn clean_all(&self, projects: &[RustProject], confirm: bool) -> Result<ExitCode> {
+        let candidates: Vec<_> = projects
+            .iter()
+            .filter(|p| !p.protected && !p.cleanable_dirs(self.cli.clean_scope()).is_empty())
+            .collect();
+
+        let rustup = if self.cli.include_rustup && !self.cli.safe {
+            let pinned: Vec<_> = projects.iter().filter_map(|p| ProjectScanner::pinned_toolchain(&p.path)).collect();
+            Self::removable_rustup_artifacts(self.cli.disk_usage, &pinned)?
+        } else {
+            Vec::new()
+        };
+        let toolchain_count = rustup.iter().filter(|a| a.artifact_type == ArtifactType::RustupToolchain).count();
+
+        self.warn_shared_targets(projects);
+        if candidates.is_empty() && rustup.is_empty() {
+            println!("Nothing to clean.");
+            return Ok(ExitCode::Success);
+        }
+
+        let mut ask_each = self.cli.confirm_each || (self.cli.auto_confirm.is_some() && !self.cli.yes);
+        // uninstalling a toolchain or cleaning a project with uncommitted changes is never
+        // done without asking (or `--yes`)
+        let dirty: HashSet<&Path> = candidates
+            .iter()
+            .filter(|p| p.has_uncommitted_changes())
+            .map(|p| p.path.as_path())
+            .collect();
+        if confirm || ((toolchain_count > 0 || !dirty.is_empty()) && !self.cli.dry_run) {
+            self.print_plan(&candidates, &rustup, &dirty);
+            if !self.cli.yes && !ask_each && !Self::ask_confirmation(candidates.len(), toolchain_count)? {
+                println!("Aborted, nothing was deleted.");
+                return Ok(ExitCode::Aborted);
+            }
+        }
+
+        if ask_each && !io::stdin().is_terminal() {
+            if self.cli.confirm_each {
+                eprintln!("No terminal to confirm on, drop --confirm-each to delete without asking.");
+            } else {
+                eprintln!("No terminal to confirm on, pass --yes to delete without asking.");
+            }
+            return Ok(ExitCode::Aborted);
+        }
+
+        let mut reclaimed = 0u64;
+        let mut cleaned_count = 0;
+        let mut failed_count = 0;
+        let mut quit = false;
+
+        for project in candidates {
+            if self.build_in_progress(project) {
+                failed_count += 1;
+                continue;
+            }
+            if self.open_in_editor(project) {
+                eprintln!("{} {}: open in an editor", "Skipped".yellow(), project.display_name());
+                continue;
+            }
+
+            if ask_each && !self.auto_confirmed(project) {
+                let dirs: Vec<_> = project
+                    .cleanable_dirs(self.cli.clean_scope())
+                    .iter()
+                    .map(|d| utils::escape_path(d))
+                    .collect();
+                match Self::ask_each(&project.display_name(), &dirs.join(", "), &project.size_label(self.cli.gb))? {
+                    EachAnswer::Yes => {}
+                    EachAnswer::No => continue,
+                    EachAnswer::All => ask_each = false,
+                    EachAnswer::Quit => {
+                        quit = true;
+                        break;
+                    }
+                }
+            }
+
+            let size = project.cleanable_size(self.cli.clean_scope());
+            if !self.pre_delete_allows(&project.path, size) {
+                continue;
+            }
+            let Some(size) = self.remove_project_dirs(project) else {
+                failed_count += 1;
+                continue;
+            };
+            self.post_delete(&project.path, size);
+            self.record_deleted(&project.path, size);
+
+            cleaned_count += 1;
+            reclaimed += size;
+            println!(
+                "{:<30} {}",
+                project.display_name(),
+                project.size_label(self.cli.gb).cyan()
+            );
+        }
+
+        for artifact in &rustup {
+            if quit {
+                break;
+            }
+            if ask_each {
+                let label = artifact.label.as_deref().unwrap_or_default();
+                let size = utils::format_size(artifact.size, self.cli.gb);
+                match Self::ask_each(label, &utils::escape_path(&artifact.path), &size)? {
+                    EachAnswer::Yes => {}
+                    EachAnswer::No => continue,
+                    EachAnswer::All => ask_each = false,
+                    EachAnswer::Quit => break,
+                }
+            }
+
+            let result = match artifact.artifact_type {
+                ArtifactType::RustupToolchain => utils::remove_rustup_toolchain(&artifact.path, self.cli.dry_run)
+                    .inspect_err(|err| self.record_error(&artifact.path, err)),
+                _ => self.remove_directory(&artifact.path),
+            };
+            match result {
+                Ok(()) => {
+                    reclaimed += artifact.size;
+                    self.report.borrow_mut().bytes_reclaimed += artifact.size;
+                    println!(
+                        "{:<30} {}",
+                        artifact.label.as_deref().unwrap_or_default(),
+                        utils::format_size(artifact.size, self.cli.gb).cyan()
+                    );
+                }
+                Err(err) => {
+                    failed_count += 1;
+                    eprintln!("{} {}", "Error:".red(), err);
+                }
+            }
+        }
+
+        let verb = if self.cli.dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!(
+            "\n{} {} across {} projects",
+            verb,
+            utils::format_size(reclaimed, self.cli.gb).bold().green(),
+            cleaned_count
+        );
+
+        if failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+
+    /// clean projects in list order until `goal` bytes are reclaimed
+    pub fn free_space(&self, projects: &[RustProject], goal: u64) -> Result<ExitCode> {
+        self.warn_shared_targets(projects);
+        let scope = self.cli.clean_scope();
+        let mut reclaimed = 0u64;
+        let mut cleaned_count = 0;
+        let mut failed_count = 0;
+
+        for project in projects.iter().filter(|p| !p.protected && p.cleanable_size(scope) > 0) {
+            if reclaimed >= goal {
+                break;
+            }
+
+            if self.build_in_progress(project) {
+                failed_count += 1;
+                continue;
+            }
+            if self.open_in_editor(project) {
+                eprintln!("{} {}: open in an editor", "Skipped".yellow(), project.display_name());
+                continue;
+            }
+
+            let size = project.cleanable_size(scope);
+            if !self.pre_delete_allows(&project.path, size) {
+                continue;
+            }
+            // the goal counts what was removed, not what the scan estimated
+            let Some(size) = self.remove_project_dirs(project) else {
+                failed_count += 1;
+                continue;
+            };
+            self.post_delete(&project.path, size);
+            self.record_deleted(&project.path, size);
+
+            cleaned_count += 1;
+            reclaimed += size;
+            println!(
+                "{:<30} {}",
+                project.display_name(),
+                utils::format_size(size, self.cli.gb).cyan()
+            );
+        }
+
+        let verb = if self.cli.dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!(
+            "\n{} {} of the {} goal across {} projects",
+            verb,
+            utils::format_size(reclaimed, self.cli.gb).bold().green(),
+            utils::format_size(goal, self.cli.gb),
+            cleaned_count
+        );
+        if reclaimed < goal {
+            eprintln!("{}", "Not enough cleanable space to reach the goal".yellow());
+        }
+
+        if failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+
+    /// remove the extracted registry sources that duplicate a cached `.crate` archive
+    pub fn dedupe_cargo_cache(&self) -> Result<ExitCode> {
+        let Some(cargo_dir) = utils::cargo_home() else {
+            println!("No cargo home found.");
+            return Ok(ExitCode::NothingFound);
+        };
+        let sources = ProjectScanner::redundant_registry_sources(&cargo_dir, self.cli.disk_usage)?;
+        if sources.is_empty() {
+            println!("No extracted crate sources duplicate the registry cache.");
+            return Ok(ExitCode::NothingFound);
+        }
+
+        // held until the sources are gone, so cargo doesn't extract into them meanwhile
+        let Some(_lock) = utils::lock_package_cache(&cargo_dir)? else {
+            eprintln!("{} {}: cargo is using the package cache", "Skipped".yellow(), cargo_dir.display());
+            self.record_error(&cargo_dir, "package cache locked by cargo");
+            return Ok(ExitCode::DeleteFailed);
+        };
+
+        let mut reclaimed = 0u64;
+        let mut removed_count = 0;
+        let mut failed_count = 0;
+        for source in &sources {
+            match self.remove_directory(&source.path) {
+                Ok(()) => {
+                    removed_count += 1;
+                    reclaimed += source.size;
+                    self.report.borrow_mut().bytes_reclaimed += source.size;
+                }
+                Err(err) => {
+                    failed_count += 1;
+                    eprintln!("{} {}", "Error:".red(), err);
+                }
+            }
+        }
+
+        let verb = if self.cli.dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!(
+            "{} {} from {} extracted crate sources, the .crate archives are kept",
+            verb,
+            utils::format_size(reclaimed, self.cli.gb).bold().green(),
+            removed_count
+        );
+
+        if failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+
+    /// name each target that several projects build into once, none of them deletes it
+    fn warn_shared_targets(&self, projects: &[RustProject]) {
+        let shared: std::collections::BTreeSet<_> = projects
+            .iter()
+            .filter(|p| p.shared_target && !p.protected)
+            .filter_map(|p| p.target_dir.as_deref())
+            .collect();
+        for target in shared {
+            eprintln!(
+                "{} {}: shared with other projects through the cargo config, clean it with `cargo clean`",
+                "Skipped".yellow(),
+                utils::escape_path(target)
+            );
+        }
+    }
+
+    /// whether cargo holds the project's build lock, a skip that's reported and counted
+    /// as a failure so the run doesn't look complete
+    fn build_in_progress(&self, project: &RustProject) -> bool {
+        if !project.target_dir.as_deref().is_some_and(utils::build_in_progress) {
+            return false;
+        }
+        self.record_error(&project.path, "a build is in progress");
+        eprintln!("{} {}: a build is in progress", "Skipped".yellow(), project.display_name());
+        true
+    }
+
+    /// with `--skip-open`, whether the project looks open in an IDE
+    fn open_in_editor(&self, project: &RustProject) -> bool {
+        self.cli.skip_open && utils::open_in_editor(&project.path, project.target_dir.as_deref())
+    }
+
+    /// whether the `--auto-confirm` policy covers a project, so it's deleted without asking
+    fn auto_confirmed(&self, project: &RustProject) -> bool {
+        match self.cli.auto_confirm {
+            Some(AutoConfirm::Stale) => !project.is_likely_active(DEFAULT_STALE_DAYS),
+            None => false,
+        }
+    }
+
+    /// whether `--pre-delete-cmd` lets a project be cleaned, a hook that can't run declines too
+    fn pre_delete_allows(&self, project_dir: &Path, size: u64) -> bool {
+        match utils::run_delete_hook(self.cli.pre_delete_cmd.as_deref(), self.cli.dry_run, project_dir, size) {
+            Ok(true) => true,
+            Ok(false) => {
+                eprintln!("{} {}: declined by the pre-delete command", "Skipped".yellow(), utils::escape_path(project_dir));
+                false
+            }
+            Err(err) => {
+                eprintln!("{} {}: pre-delete command: {}", "Skipped".yellow(), utils::escape_path(project_dir), err);
+                false
+            }
+        }
+    }
+
+    /// run `--post-delete-cmd`, the deletion already happened so a failure is only reported
+    fn post_delete(&self, project_dir: &Path, size: u64) {
+        match utils::run_delete_hook(self.cli.post_delete_cmd.as_deref(), self.cli.dry_run, project_dir, size) {
+            Ok(true) => {}
+            Ok(false) => eprintln!("{} post-delete command failed for {}", "Warning:".yellow(), utils::escape_path(project_dir)),
+            Err(err) => eprintln!("{} post-delete command: {}", "Warning:".yellow(), err),
+        }
+    }
+
+    /// remove a directory, with `--fixup-permissions` a permission denied error is
+    /// retried through `sudo` once the user confirms it
+    fn remove_directory(&self, path: &Path) -> crate::error::Result<()> {
+        self.throttle();
+        let result = match utils::remove_directory(path, self.cli.dry_run) {
+            Err(RskillError::PermissionDenied { path: denied }) if self.cli.fixup_permissions => {
+                self.retry_with_sudo(&denied)
+            }
+            result => result,
+        };
+        self.last_deletion.set(Some(Instant::now()));
+        if let Err(err) = &result {
+            self.record_error(path, err);
+        }
+        result
+    }
+
+    /// with `--throttle`, wait until that long has passed since the previous deletion
+    fn throttle(&self) {
+        let (Some(throttle), Some(last)) = (self.cli.throttle, self.last_deletion.get()) else {
+            return;
+        };
+        if self.cli.dry_run {
+            return;
+        }
+        std::thread::sleep(throttle.saturating_sub(last.elapsed()));
+    }
+
+    #[cfg(unix)]
+    fn retry_with_sudo(&self, path: &Path) -> crate::error::Result<()> {
+        if !Self::ask_sudo(path)? {
+            return Err(RskillError::PermissionDenied { path: path.to_path_buf() });
+        }
+        println!("Removing {} with sudo...", utils::escape_path(path));
+        utils::remove_directory_with_sudo(path)
+    }
+
+    #[cfg(not(unix))]
+    fn retry_with_sudo(&self, path: &Path) -> crate::error::Result<()> {
+        eprintln!("{} --fixup-permissions is only supported on Unix", "Warning:".yellow());
+        Err(RskillError::PermissionDenied { path: path.to_path_buf() })
+    }
+
+    /// ask before escalating, without a terminal the deletion is never retried
+    #[cfg(unix)]
+    fn ask_sudo(path: &Path) -> io::Result<bool> {
+        if !io::stdin().is_terminal() {
+            eprintln!("No terminal to confirm on, not retrying {} with sudo.", utils::escape_path(path));
+            return Ok(false);
+        }
+
+        Self::prompt_yes_no(&format!("Permission denied: {}. Retry with sudo?", utils::escape_path(path)))
+    }
+
+    /// delete every cleanable directory of a project, stopping at the first failure;
+    /// the bytes of the directories that were removed, `None` on a failure
+    fn remove_project_dirs(&self, project: &RustProject) -> Option<u64> {
+        let mut removed = 0u64;
+        for dir in project.cleanable_dirs(self.cli.clean_scope()).iter().filter(|d| d.exists()) {
+            let size = self.removed_size(project, dir);
+            if project.target_symlink && project.target_dir.as_ref() == Some(dir) {
+                eprintln!("{} {} is a symlink, only the link is removed", "Warning:".yellow(), dir.display());
+            }
+            if project.is_vendored_deps(dir) {
+                eprintln!("{} {} holds vendored dependencies, run `cargo vendor` before building again", "Warning:".yellow(), dir.display());
+            }
+            if let Err(err) = self.remove_directory(dir) {
+                eprintln!("{} {}", "Error:".red(), err);
+                return None;
+            }
+            removed += size;
+        }
+        Some(removed)
+    }
+
+    /// what removing one of the project's cleanable directories frees, unlinking a
+    /// symlinked target frees nothing
+    fn removed_size(&self, project: &RustProject, dir: &Path) -> u64 {
+        if project.target_dir.as_deref() == Some(dir) {
+            return if project.target_symlink { 0 } else { project.target_size };
+        }
+        match project.build_artifacts.iter().find(|a| a.path == dir) {
+            Some(artifact) => artifact.size,
+            None if self.cli.no_sizes => 0,
+            None => utils::calculate_dir_size(dir, self.cli.disk_usage).unwrap_or(0),
+        }
+    }
+
+    /// every toolchain but the default one and those the scanned projects pin, plus the
+    /// download cache; without a known default toolchain no toolchain is touched
+    fn removable_rustup_artifacts(disk_usage: bool, pinned: &[String]) -> Result<Vec<BuildArtifact>> {
+        let Some(default_toolchain) = utils::default_rustup_toolchain() else {
+            return Ok(ProjectScanner::rustup_artifacts(disk_usage)?
+                .into_iter()
+                .filter(|a| matches!(a.artifact_type, ArtifactType::RustupDownloads))
+                .collect());
+        };
+
+        Ok(ProjectScanner::rustup_artifacts(disk_usage)?
+            .into_iter()
+            .filter(|a| {
+                let name = a.path.file_name().unwrap_or_default().to_string_lossy();
+                a.artifact_type != ArtifactType::RustupToolchain
+                    || (name != default_toolchain.as_str() && !Self::is_pinned(&name, pinned))
+            })
+            .collect())
+    }
+
+    /// whether an installed toolchain (`1.75.0-x86_64-unknown-linux-gnu`) is the one a
+    /// pinned channel (`1.75.0`) resolves to
+    fn is_pinned(toolchain: &str, pinned: &[String]) -> bool {
+        pinned
+            .iter()
+            .any(|channel| toolchain == channel || toolchain.strip_prefix(channel.as_str()).is_some_and(|rest| rest.starts_with('-')))
+    }
+
+    fn print_plan(&self, projects: &[&RustProject], rustup: &[BuildArtifact], dirty: &HashSet<&Path>) {
+        println!("{}", "The following directories will be deleted:".bold());
+        for project in projects {
+            let building = project.target_dir.as_deref().is_some_and(utils::build_in_progress);
+            let open = self.open_in_editor(project);
+            for dir in project.cleanable_dirs(self.cli.clean_scope()) {
+                if open {
+                    println!("  {} {}", utils::escape_path(&dir), "(open in an editor, skipped)".yellow());
+                } else if building && project.target_dir.as_ref().is_some_and(|target| dir.starts_with(target)) {
+                    println!("  {} {}", utils::escape_path(&dir), "(build in progress?)".yellow());
+                } else if dirty.contains(project.path.as_path()) {
+                    println!("  {} {}", utils::escape_path(&dir), "(uncommitted changes)".yellow());
+                } else if project.is_vendored_deps(&dir) {
+                    println!("  {} {}", utils::escape_path(&dir), "(vendored, regenerate with `cargo vendor`)".yellow());
+                } else {
+                    println!("  {}", utils::escape_path(&dir));
+                }
+            }
+        }
+        for artifact in rustup {
+            match artifact.artifact_type {
+                ArtifactType::RustupToolchain => println!(
+                    "  {} {}",
+                    utils::escape_path(&artifact.path),
+                    format!("(toolchain {}, uninstalled with rustup)", artifact.label.as_deref().unwrap_or_default()).yellow()
+                ),
+                _ => println!("  {}", utils::escape_path(&artifact.path)),
+            }
+        }
+
+        let total: u64 = projects.iter().map(|p| p.cleanable_size(self.cli.clean_scope())).sum::<u64>()
+            + rustup.iter().map(|a| a.size).sum::<u64>();
+        let total_str = if self.cli.no_sizes {
+            "unknown".to_string()
+        } else {
+            utils::format_size(total, self.cli.gb)
+        };
+        println!("\n{} projects, {} in total", projects.len(), total_str.bold());
+    }
+
+    /// prompt once on the terminal, anything but `y` (or no terminal at all) declines
+    fn ask_confirmation(project_count: usize, toolchain_count: usize) -> Result<bool> {
+        if !io::stdin().is_terminal() {
+            eprintln!("No terminal to confirm on, pass --yes to delete without asking.");
+            return Ok(false);
+        }
+
+        let question = if toolchain_count > 0 {
+            format!("Delete artifacts of {} projects and uninstall {} toolchains?", project_count, toolchain_count)
+        } else {
+            format!("Delete artifacts of {} projects?", project_count)
+        };
+        Ok(Self::prompt_yes_no(&question)?)
+    }
+
+    /// ask a y/N question on stderr, anything but `y` declines
+    fn prompt_yes_no(question: &str) -> io::Result<bool> {
+        eprint!("{} [y/N] ", question);
+        io::stderr().flush()?;
+
+        let mut answer = String::new();
+        io::stdin().lock().read_line(&mut answer)?;
+        Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+    }
+
+    /// the size and rebuild consequence of deleting one artifact type, then a y/N prompt
+    fn confirm_artifact_type(&self, projects: &[RustProject], artifact_type: &ArtifactType, what: &str) -> Result<bool> {
+        let (count, size) = projects
+            .iter()
+            .filter(|p| !p.protected)
+            .map(|p| p.build_artifacts.iter().filter(|a| a.artifact_type == *artifact_type).map(|a| a.size).sum::<u64>())
+            .filter(|size| *size > 0)
+            .fold((0, 0u64), |(count, total), size| (count + 1, total + size));
+        if count == 0 {
+            return Ok(true);
+        }
+
+        println!("{} {} of {} projects, {}", "Deleting the".bold(), what, count, utils::format_size(size, self.cli.gb).bold());
+        println!("{} {}", "Rebuild:".yellow(), artifact_type.rebuild_consequence());
+        if !io::stdin().is_terminal() {
+            eprintln!("No terminal to confirm on, pass --yes to delete without asking.");
+            return Ok(false);
+        }
+
+        Ok(Self::prompt_yes_no(&format!("Delete the {}?", what))?)
+    }
+
+    /// prompt for a single item until one of y/n/a/q is given, end of input quits
+    fn ask_each(name: &str, path: &str, size: &str) -> Result<EachAnswer> {
+        eprintln!("\n{} {}\n  {}", name.bold(), size.cyan(), path);
+        loop {
+            eprint!("Delete? [y,n,a,q] ");
+            io::stderr().flush()?;
+
+            let mut answer = String::new();
+            if io::stdin().lock().read_line(&mut answer)? == 0 {
+                return Ok(EachAnswer::Quit);
+            }
+            match answer.trim() {
+                "y" | "Y" => return Ok(EachAnswer::Yes),
+                "n" | "N" => return Ok(EachAnswer::No),
+                "a" | "A" => return Ok(EachAnswer::All),
+                "q" | "Q" => return Ok(EachAnswer::Quit),
+                _ => eprintln!("y: delete, n: skip, a: delete this and all remaining, q: quit"),
+            }
+        }
+    }
+
+    /// delete only the incremental compilation caches, keeping compiled dependencies
+    pub fn clean_incremental(&self, projects: &[RustProject]) -> Result<ExitCode> {
+        self.clean_artifacts_of_type(projects, ArtifactType::IncrementalCompilation, "incremental cache")
+    }
+
+    /// delete only the generated documentation, keeping compiled artifacts
+    pub fn clean_docs(&self, projects: &[RustProject]) -> Result<ExitCode> {
+        self.clean_artifacts_of_type(projects, ArtifactType::Documentation, "documentation")
+    }
+
+    /// delete only the compiled dependencies, every one is compiled again on the next build
+    pub fn clean_deps(&self, projects: &[RustProject]) -> Result<ExitCode> {
+        self.clean_artifacts_of_type(projects, ArtifactType::Dependencies, "compiled dependencies")
+    }
+
+    /// with `--confirm`, show what the deletion costs on the next build and ask before deleting
+    fn clean_artifacts_of_type(&self, projects: &[RustProject], artifact_type: ArtifactType, what: &str) -> Result<ExitCode> {
+        let confirm = self.cli.confirm && !self.cli.yes && !self.cli.dry_run;
+        if confirm && !self.confirm_artifact_type(projects, &artifact_type, what)? {
+            println!("Aborted, nothing was deleted.");
+            return Ok(ExitCode::Aborted);
+        }
+
+        let mut reclaimed = 0u64;
+        let mut cleaned_count = 0;
+        let mut failed_count = 0;
+
+        for project in projects.iter().filter(|p| !p.protected) {
+            let artifacts: Vec<_> = project
+                .build_artifacts
+                .iter()
+                .filter(|a| a.artifact_type == artifact_type)
+                .collect();
+
+            if artifacts.is_empty() {
+                continue;
+            }
+            // like `cleanable_dirs`, never reach through a symlinked or shared target
+            if (project.target_symlink || project.shared_target)
+                && project.target_dir.as_ref().is_some_and(|target| artifacts.iter().any(|a| a.path.starts_with(target)))
+            {
+                eprintln!("{} {}: its target is a symlink or shared", "Skipped".yellow(), project.display_name());
+                continue;
+            }
+            if self.build_in_progress(project) {
+                failed_count += 1;
+                continue;
+            }
+            if self.open_in_editor(project) {
+                eprintln!("{} {}: open in an editor", "Skipped".yellow(), project.display_name());
+                continue;
+            }
+
+            let size = artifacts.iter().map(|a| a.size).sum();
+            if !self.pre_delete_allows(&project.path, size) {
+                continue;
+            }
+
+            let mut project_reclaimed = 0u64;
+            for artifact in artifacts {
+                match self.remove_directory(&artifact.path) {
+                    Ok(()) => project_reclaimed += artifact.size,
+                    Err(err) => {
+                        failed_count += 1;
+                        eprintln!("{} {}", "Error:".red(), err);
+                    }
+                }
+            }
+            self.post_delete(&project.path, project_reclaimed);
+
+            if project_reclaimed > 0 {
+                self.record_deleted(&project.path, project_reclaimed);
+                cleaned_count += 1;
+                reclaimed += project_reclaimed;
+                println!(
+                    "{:<30} {}",
+                    project.display_name(),
+                    utils::format_size(project_reclaimed, self.cli.gb).cyan()
+                );
+            }
+        }
+
+        let verb = if self.cli.dry_run { "Would reclaim" } else { "Reclaimed" };
+        println!(
+            "\n{} {} of {} across {} projects",
+            verb,
+            utils::format_size(reclaimed, self.cli.gb).bold().green(),
+            what,
+            cleaned_count
+        );
+
+        if failed_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::ScanStats;
+    use clap::Parser;
+    use std::fs;
+
+    fn scan(dir: &Path, cli: &Cli) -> Vec<RustProject> {
+        ProjectScanner::find_rust_projects(dir, &cli.get_excluded_dirs(), cli, &mut ScanStats::default(), None).unwrap()
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_free_space_counts_only_removed_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("app/Cargo.toml"), "[package]\nname = \"app\"\n");
+        write(&dir.path().join("app/target/debug/app"), "binary");
+        let project = RustProject {
+            target_dir: Some(dir.path().join("app/target")),
+            cargo_cache_size: 1 << 30,
+            ..RustProject::fixture(dir.path().join("app").to_str().unwrap(), 6)
+        };
+
+        let cleaner = BatchCleaner::new(Cli::parse_from(["rskill"]));
+        cleaner.free_space(&[project], 1 << 20).unwrap();
+
+        assert!(!dir.path().join("app/target").exists());
+        assert_eq!(cleaner.report.borrow().bytes_reclaimed, 6);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_incremental_skips_a_symlinked_target() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("app/Cargo.toml"), "[package]\nname = \"app\"\n");
+        write(&dir.path().join("cache/debug/incremental/app-1/query-cache.bin"), "cache");
+        std::os::unix::fs::symlink(dir.path().join("cache"), dir.path().join("app/target")).unwrap();
+
+        let cli = Cli::parse_from(["rskill", "-d", dir.path().to_str().unwrap()]);
+        let projects = scan(dir.path(), &cli);
+        assert!(projects[0].target_symlink);
+        assert!(projects[0].build_artifacts.iter().any(|a| a.artifact_type == ArtifactType::IncrementalCompilation));
+
+        BatchCleaner::new(cli).clean_incremental(&projects).unwrap();
+        assert!(dir.path().join("cache/debug/incremental/app-1/query-cache.bin").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_clean_incremental_skips_a_locked_target() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("app/Cargo.toml"), "[package]\nname = \"app\"\n");
+        write(&dir.path().join("app/target/debug/incremental/app-1/query-cache.bin"), "cache");
+        let lock = fs::File::create(dir.path().join("app/target/debug/.cargo-lock")).unwrap();
+        lock.lock().unwrap();
+
+        let cli = Cli::parse_from(["rskill", "-d", dir.path().to_str().unwrap()]);
+        let projects = scan(dir.path(), &cli);
+        let code = BatchCleaner::new(cli).clean_incremental(&projects).unwrap();
+
+        assert_eq!(code, ExitCode::DeleteFailed);
+        assert!(dir.path().join("app/target/debug/incremental/app-1/query-cache.bin").exists());
+    }
+
+    #[test]
+    fn test_report_counts_a_pruned_and_cleaned_project_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = dir.path().join("app");
+        fs::create_dir_all(app.join("target/debug")).unwrap();
+        write(&app.join("dist/index.html"), "12345");
+        let mut projects = vec![RustProject {
+            target_dir: Some(app.join("target")),
+            extra_artifacts: vec![app.join("dist")],
+            extra_artifacts_size: 5,
+            ..RustProject::fixture(app.to_str().unwrap(), 0)
+        }];
+
+        let report_path = dir.path().join("report.json");
+        let cleaner = BatchCleaner::new(Cli::parse_from(["rskill", "--report-json", report_path.to_str().unwrap()]));
+        cleaner.record_scanned(projects.len());
+        cleaner.prune_empty_targets(&mut projects).unwrap();
+        cleaner.clean_all(&projects, false).unwrap();
+        cleaner.save_report().unwrap();
+
+        let report: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report_path).unwrap()).unwrap();
+        assert_eq!(report["projects_scanned"], 1);
+        assert_eq!(report["projects_deleted"], 1);
+        assert_eq!(report["bytes_reclaimed"], 5);
+        assert_eq!(report["errors"], serde_json::json!([]));
+        assert!(!app.join("dist").exists());
+    }
+
+    #[test]
+    fn test_pinned_toolchains_match_their_installed_names() {
+        let pinned = ["1.75.0".to_string(), "nightly-2024-01-01".to_string()];
+
+        assert!(BatchCleaner::is_pinned("1.75.0-x86_64-unknown-linux-gnu", &pinned));
+        assert!(BatchCleaner::is_pinned("nightly-2024-01-01-aarch64-apple-darwin", &pinned));
+        assert!(!BatchCleaner::is_pinned("1.75.1-x86_64-unknown-linux-gnu", &pinned));
+        assert!(!BatchCleaner::is_pinned("nightly-x86_64-unknown-linux-gnu", &pinned));
+    }
+
+    #[test]
+    fn test_cleaning_a_member_keeps_the_configured_shared_target() {
+        let dir = tempfile::tempdir().unwrap();
+        let ws = dir.path().join("ws");
+        write(&ws.join("Cargo.toml"), "[workspace]\nmembers = [\"one\", \"two\"]\n");
+        write(&ws.join(".cargo/config.toml"), "[build]\ntarget-dir = \"../build\"\n");
+        for member in ["one", "two"] {
+            write(&ws.join(member).join("Cargo.toml"), &format!("[package]\nname = \"{}\"\n", member));
+        }
+        write(&dir.path().join("build/debug/one"), "binary");
+
+        let cli = Cli::parse_from(["rskill", "-d", dir.path().to_str().unwrap()]);
+        let projects = scan(dir.path(), &cli);
+        let one = projects.iter().find(|p| p.name == "one").unwrap();
+        assert!(one.shared_target);
+        assert_eq!(one.cleanable_size(cli.clean_scope()), 0);
+
+        let code = BatchCleaner::new(cli).clean_all(std::slice::from_ref(one), false).unwrap();
+        assert_eq!(code, ExitCode::Success);
+        assert!(dir.path().join("build/debug/one").exists());
+    }
+}
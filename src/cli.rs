@@ -1,17 +1,42 @@
-use std::path::PathBuf;
+use std::{io::IsTerminal, path::PathBuf, time::Duration};
 use clap::{Parser, ValueEnum};
+use serde::Deserialize;
+use crate::{config::Config, project::CleanScope, units};
 
 #[derive(Parser, Clone, Debug)]
 #[command(
     name = "rskill",
     about = "Find and clean Rust project build artifacts and caches",
-    version = "0.3.3"
+    version = "0.3.3",
+    // a later flag replaces an earlier one, so flags given after a `--preset` win
+    args_override_self = true,
+    after_help = "Exit codes:\n  \
+        0  success\n  \
+        1  scan error\n  \
+        2  one or more deletions failed\n  \
+        3  no Rust projects found\n  \
+        4  aborted by the user\n  \
+        5  cleaned, but the `--rebuild-after` build failed"
 )]
 pub struct Cli {
+    /// expand to a named set of flags: aggressive, conservative, ci, or one from the
+    /// config file's `[presets]`; flags given explicitly take precedence
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<String>,
+
     /// directory to start searching from current working directory
     #[arg(short, long, default_value = ".")]
     pub directory: PathBuf,
 
+    /// run `cargo build` (or the config's `rebuild_command`) in a project right after
+    /// cleaning it, only for single projects, never with `--delete-all`
+    #[arg(long, conflicts_with = "delete_all")]
+    pub rebuild_after: bool,
+
+    /// skip the walk and clean the project in the search directory, after confirmation
+    #[arg(long, conflicts_with = "full")]
+    pub here: bool,
+
     /// search from user's home directory
     #[arg(short = 'f', long)]
     pub full: bool,
@@ -24,18 +49,88 @@ pub struct Cli {
     #[arg(short, long, value_enum, default_value = "size")]
     pub sort: SortBy,
 
+    /// order projects that tie on `--sort` by this key (ties are always finally broken by path)
+    #[arg(long, value_enum, value_name = "SORT")]
+    pub then_by: Option<SortBy>,
+
+    /// reverse the sort order
+    #[arg(long)]
+    pub reverse: bool,
+
+    /// how to render last-modified times (default: date in the list, relative in the TUI)
+    #[arg(long, value_enum)]
+    pub timestamps: Option<TimestampFormat>,
+
+    /// comma separated metrics for the TUI footer, in order (overrides the config's `footer`)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "METRICS")]
+    pub footer: Option<Vec<FooterMetric>>,
+
+    /// wrap the TUI selection around from the last project to the first and back
+    #[arg(long)]
+    pub wrap: bool,
+
+    /// how to show project paths in the table, the TUI and JSON
+    #[arg(long, value_enum, default_value = "auto")]
+    pub paths: PathStyle,
+
+    /// when to color output, `auto` colors only a terminal and honors NO_COLOR
+    #[arg(long, value_enum, default_value = "auto")]
+    pub color: ColorChoice,
+
     /// show sizes in gigabytes instead of megabytes
     #[arg(long)]
     pub gb: bool,
 
-    /// exclude directories from search (comma-separated)
+    /// report allocated disk usage (like `du`) instead of apparent file sizes,
+    /// closer to the space actually reclaimed on compressed or large-block filesystems
+    #[arg(long)]
+    pub disk_usage: bool,
+
+    /// skip computing target sizes for near-instant discovery (sizes show as unknown,
+    /// size sorting falls back to path)
+    #[arg(long, alias = "no-target-size")]
+    pub no_sizes: bool,
+
+    /// clean projects in list order until this much space is reclaimed (e.g. `20G`),
+    /// `--sort last-mod --reverse` cleans the oldest first
+    #[arg(long, value_name = "SIZE", value_parser = units::parse_size, conflicts_with = "no_sizes")]
+    pub free: Option<u64>,
+
+    /// stop sizing a target directory once it passes this size (e.g. `5G`), such
+    /// targets are shown as at least the cap
+    #[arg(long, value_name = "SIZE", value_parser = units::parse_size)]
+    pub size_cap: Option<u64>,
+
+    /// note the largest single file in each target directory, shown in the details pane
+    /// and the JSON output
+    #[arg(long, conflicts_with = "no_sizes")]
+    pub largest_file: bool,
+
+    /// exclude directories from search (comma-separated), added to those in `RSKILL_EXCLUDE`
     #[arg(short = 'E', long)]
     pub exclude: Option<String>,
 
+    /// follow symbolic links while searching (projects reachable twice are listed once)
+    #[arg(long)]
+    pub follow_links: bool,
+
     /// exclude hidden directories
     #[arg(short = 'x', long)]
     pub exclude_hidden: bool,
 
+    /// exclude the project the current working directory is in
+    #[arg(long)]
+    pub exclude_cwd: bool,
+
+    /// skip workspace member crates, listing only their workspace root
+    #[arg(long)]
+    pub exclude_workspace_members: bool,
+
+    /// only treat a `Cargo.toml` as a project when it parses and has a `[package]` or
+    /// `[workspace]` table, skipping templates and fragments with a note
+    #[arg(long)]
+    pub verify_cargo_toml: bool,
+
     /// hide errors
     #[arg(short = 'e', long)]
     pub hide_errors: bool,
@@ -44,30 +139,277 @@ pub struct Cli {
     #[arg(short = 'D', long)]
     pub delete_all: bool,
 
+    /// write the directories that would be deleted to a JSON plan file instead of deleting
+    #[arg(long, value_name = "FILE", conflicts_with = "apply")]
+    pub plan: Option<PathBuf>,
+
+    /// delete exactly the directories of a plan written by `--plan`, skipping any that grew
+    #[arg(long, value_name = "FILE")]
+    pub apply: Option<PathBuf>,
+
+    /// save the scan results to a snapshot file for a later `--compare`
+    #[arg(long, value_name = "FILE")]
+    pub snapshot: Option<PathBuf>,
+
+    /// show how each project's cleanable size changed since a saved snapshot
+    #[arg(long, value_name = "FILE")]
+    pub compare: Option<PathBuf>,
+
+    /// write a nested JSON breakdown of cleanable sizes for d3 treemaps, or folded
+    /// stacks for inferno when FILE ends in `.folded`
+    #[arg(long, value_name = "FILE")]
+    pub treemap: Option<PathBuf>,
+
+    /// remove target directories that contain no files, on its own or before `--delete-all --list-only`
+    #[arg(long, alias = "delete-empty-target")]
+    pub prune_empty: bool,
+
+    /// with `--delete-all --list-only`, print the full deletion plan and ask once before deleting;
+    /// with `--clean-incremental-only`, `--clean-docs-only` or `--clean-deps-only`, show what the
+    /// rebuild costs and ask
+    #[arg(long)]
+    pub confirm: bool,
+
+    /// with `--delete-all --list-only`, ask before each project: y(es), n(o), a(ll), q(uit)
+    #[arg(long)]
+    pub confirm_each: bool,
+
+    /// with `--delete-all --list-only` or `--here`, delete projects matching the policy
+    /// without asking and ask about each of the others; `--yes` still confirms everything
+    #[arg(long, value_enum, value_name = "POLICY")]
+    pub auto_confirm: Option<AutoConfirm>,
+
+    /// assume yes for the `--confirm` and `--here` prompts
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+
+    /// run before each project is cleaned, with its path and size in bytes as arguments
+    /// (and in RSKILL_PROJECT_PATH/RSKILL_SIZE); a non-zero exit skips the project
+    #[arg(long, value_name = "COMMAND")]
+    pub pre_delete_cmd: Option<String>,
+
+    /// run after each project is cleaned, with the same arguments as `--pre-delete-cmd`
+    #[arg(long, value_name = "COMMAND")]
+    pub post_delete_cmd: Option<String>,
+
+    /// when a batch deletion hits permission denied, ask to retry it with `sudo` (Unix only),
+    /// e.g. for target directories left behind by a CI job running as root
+    #[arg(long, alias = "sudo")]
+    pub fixup_permissions: bool,
+
+    /// leave projects that look open in an IDE alone: editor state (`.idea`, `.vscode`,
+    /// `.zed`) or rust-analyzer's target directory written to within the last hour
+    #[arg(long)]
+    pub skip_open: bool,
+
+    /// only delete regenerable build output, never caches or toolchains
+    #[arg(long)]
+    pub safe: bool,
+
     /// dry run - don't actually delete anything
     #[arg(long)]
     pub dry_run: bool,
 
+    /// after deleting, write the outcome (deleted projects, reclaimed bytes, errors) as
+    /// JSON to FILE for CI to check
+    #[arg(long, value_name = "FILE")]
+    pub report_json: Option<PathBuf>,
+
+    /// only include projects not modified for at least this many days (or e.g. `2w`, `6mo`)
+    /// (projects with unknown modification time are excluded)
+    #[arg(long, value_name = "DAYS", value_parser = units::parse_days)]
+    pub older_than: Option<i64>,
+
+    /// only include projects modified within the last this many days (or e.g. `2w`, `6mo`)
+    /// (projects with unknown modification time are excluded)
+    #[arg(long, value_name = "DAYS", value_parser = units::parse_days)]
+    pub newer_than: Option<i64>,
+
+    /// delete only the incremental compilation caches (target/*/incremental),
+    /// keeping compiled dependencies so rebuilds stay cheap
+    #[arg(long)]
+    pub clean_incremental_only: bool,
+
+    /// delete only the generated documentation (target/doc), keeping compiled artifacts
+    #[arg(long)]
+    pub clean_docs_only: bool,
+
+    /// delete only the compiled dependencies (target/*/deps), every dependency is compiled
+    /// again on the next build
+    #[arg(long, conflicts_with_all = ["clean_incremental_only", "clean_docs_only"])]
+    pub clean_deps_only: bool,
+
+    /// keep the generated documentation (target/doc) when cleaning a target directory
+    #[arg(long)]
+    pub keep_docs: bool,
+
+    /// never touch a target directory that is a symlink (to a ramdisk or shared cache),
+    /// by default only the link itself is removed
+    #[arg(long)]
+    pub exclude_symlinked_targets: bool,
+
     /// just list projects without interactive mode
     #[arg(short, long)]
     pub list_only: bool,
 
+    /// add a rough rebuild cost (cheap/moderate/expensive) column to the list, and whether
+    /// the sources changed since the last build (a stale build is cheap to clean)
+    #[arg(long)]
+    pub rebuild_cost: bool,
+
+    /// add columns with each project's share of the total reclaimable space and, where
+    /// `df` can tell, of its disk's capacity
+    #[arg(long, conflicts_with = "no_sizes")]
+    pub show_percent: bool,
+
+    /// in list mode, estimate how much of the compiled dependencies are duplicated across
+    /// projects and would be saved by a shared target directory
+    #[arg(long, conflicts_with = "no_sizes")]
+    pub dedup_report: bool,
+
+    /// only print the total cleanable size and project counts, no per-project rows
+    #[arg(long)]
+    pub summary_only: bool,
+
+    /// in list mode, print subtotals per directory DEPTH levels below the search directory
+    /// instead of one row per project
+    #[arg(long, value_name = "DEPTH")]
+    pub group_by_parent: Option<usize>,
+
+    /// in list mode, print one compact line per project with a bar scaled to the largest one
+    #[arg(long, conflicts_with = "no_sizes")]
+    pub bars: bool,
+
+    /// print the projects and their per-artifact-type sizes as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+
+    /// print each project as a JSON line as soon as it's found, unsorted and without
+    /// `--top`/`--max-results`, for piping long scans into other tools
+    #[arg(long, conflicts_with = "json")]
+    pub jsonl: bool,
+
+    /// only keep the N largest projects (by cleanable size)
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// keep at most N projects while scanning, evicting the smallest as larger ones are
+    /// found; unlike `--top` this bounds memory on huge trees, the kept projects are
+    /// the largest regardless of `--sort`, which then orders them
+    #[arg(long, value_name = "N")]
+    pub max_results: Option<usize>,
+
+    /// stop walking after this long (e.g. `90s`, `5m`) and go on with the projects found so far
+    #[arg(long, value_name = "DURATION", value_parser = units::parse_duration)]
+    pub timeout: Option<Duration>,
+
+    /// pause this long between deletions (e.g. `500ms`, `2s`) so a mass clean doesn't
+    /// saturate the disk
+    #[arg(long, value_name = "DURATION", value_parser = units::parse_duration)]
+    pub throttle: Option<Duration>,
+
+    /// print how long each scan stage took to stderr once done
+    #[arg(long, hide = true)]
+    pub profile: bool,
+
     /// show additional Rust-specific directories (registry cache, git cache, etc.)
     #[arg(long)]
     pub include_cargo_cache: bool,
 
+    /// remove extracted crate sources under `~/.cargo/registry/src` whose `.crate` archive
+    /// is still cached, cargo extracts them again when a build needs them
+    #[arg(long)]
+    pub dedupe_cargo_cache: bool,
+
+    /// look inside tar (plain, .gz, .zst) and zip archives found while scanning and
+    /// report how much of each archived crate is `target/`, without extracting; listings
+    /// only, and needs the `archives` feature (on by default)
+    #[arg(long)]
+    pub peek_archives: bool,
+
+    /// show installed rustup toolchains and downloads, and with `--delete-all --list-only`
+    /// uninstall every toolchain except the default and the pinned ones after asking, unless `--safe`
+    #[arg(long)]
+    pub include_rustup: bool,
+
     /// don't check for updates
     #[arg(long)]
     pub no_check_update: bool,
+
+    /// settings from the config file, loaded after parsing
+    #[arg(skip)]
+    pub config: Config,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SortBy {
     Size,
     Path,
     LastMod,
 }
 
+#[derive(ValueEnum, Clone, Debug)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// a piece of the TUI status footer, see `--footer`
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FooterMetric {
+    /// number of projects listed
+    Projects,
+    /// total cleanable size
+    Cleanable,
+    /// how long the scan took
+    ScanTime,
+    /// projects past the stale cutoff
+    Stale,
+    /// current sort order
+    Sort,
+    /// projects deleted so far and the failures
+    Deleted,
+    /// the most used keys
+    Keys,
+}
+
+/// footer shown when neither `--footer` nor the config choose one
+pub const DEFAULT_FOOTER: &[FooterMetric] = &[
+    FooterMetric::Projects,
+    FooterMetric::Cleanable,
+    FooterMetric::ScanTime,
+    FooterMetric::Stale,
+    FooterMetric::Deleted,
+    FooterMetric::Keys,
+];
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutoConfirm {
+    /// projects untouched for longer than the stale cutoff (30 days)
+    Stale,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum PathStyle {
+    /// relative below the working directory, absolute elsewhere
+    Auto,
+    /// relative to the working directory, climbing out of it with `..`
+    Relative,
+    Absolute,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum TimestampFormat {
+    /// RFC3339, e.g. 2024-06-01T14:32:10Z
+    Iso,
+    /// e.g. "3 days ago"
+    Relative,
+    /// e.g. 2024-06-01
+    Date,
+}
+
 impl Cli {
     pub fn get_search_directory(&self) -> PathBuf {
         if self.full {
@@ -77,14 +419,147 @@ impl Cli {
         }
     }
 
+    /// check a project's age in days against `--older-than` / `--newer-than`,
+    /// both bounds may be combined to select a window
+    pub fn matches_age_filters(&self, days_since_modified: Option<i64>) -> bool {
+        if self.older_than.is_none() && self.newer_than.is_none() {
+            return true;
+        }
+
+        let Some(days) = days_since_modified else {
+            return false;
+        };
+
+        self.older_than.is_none_or(|min| days >= min)
+            && self.newer_than.is_none_or(|max| days < max)
+    }
+
+    /// resolve `--color` against the environment and whether stdout is a terminal
+    pub fn use_color(&self) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+
+    pub fn clean_scope(&self) -> CleanScope {
+        CleanScope {
+            safe_only: self.safe,
+            keep_docs: self.keep_docs,
+            skip_symlinked_targets: self.exclude_symlinked_targets,
+        }
+    }
+
+    pub fn footer_metrics(&self) -> &[FooterMetric] {
+        self.footer
+            .as_deref()
+            .or(self.config.footer.as_deref())
+            .unwrap_or(DEFAULT_FOOTER)
+    }
+
+    /// the flags a preset stands for, presets from the config file shadow the built-in ones
+    pub fn preset_args(&self, name: &str) -> Option<Vec<String>> {
+        self.config
+            .presets
+            .get(name)
+            .cloned()
+            .or_else(|| builtin_preset(name).map(|args| args.iter().map(|arg| arg.to_string()).collect()))
+    }
+
+    pub fn rebuild_command(&self) -> &str {
+        self.config.rebuild_command.as_deref().unwrap_or("cargo build")
+    }
+
+    /// `RSKILL_EXCLUDE` (comma or colon separated) plus `--exclude`
     pub fn get_excluded_dirs(&self) -> Vec<String> {
-        self.exclude
-            .as_ref()
-            .map(|s| {
-                s.split(',')
-                    .map(|dir| dir.trim().to_string())
-                    .collect()
-            })
-            .unwrap_or_default()
+        let env_excludes = std::env::var("RSKILL_EXCLUDE").ok();
+        merge_excludes(env_excludes.as_deref(), self.exclude.as_deref())
+    }
+}
+
+pub const BUILTIN_PRESETS: [&str; 3] = ["aggressive", "conservative", "ci"];
+
+fn builtin_preset(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        // every project's artifacts after one confirmation, the shared caches and
+        // toolchains are left alone
+        "aggressive" => Some(&["--delete-all", "--list-only", "--confirm"]),
+        // only the incremental caches of stale projects
+        "conservative" => Some(&["--clean-incremental-only", "--older-than", "30"]),
+        // headless cleaning, the outcome is in the exit code
+        "ci" => Some(&["--delete-all", "--list-only", "--yes", "--no-check-update", "--color", "never"]),
+        _ => None,
+    }
+}
+
+fn merge_excludes(env_excludes: Option<&str>, cli_excludes: Option<&str>) -> Vec<String> {
+    let env_dirs = env_excludes.into_iter().flat_map(|s| s.split([',', ':']));
+    let cli_dirs = cli_excludes.into_iter().flat_map(|s| s.split(','));
+
+    // an empty entry would match every path
+    env_dirs
+        .chain(cli_dirs)
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_args_and_precedence() {
+        let mut cli = Cli::parse_from(["rskill"]);
+        assert_eq!(cli.preset_args("conservative").unwrap(), ["--clean-incremental-only", "--older-than", "30"]);
+        assert!(cli.preset_args("missing").is_none());
+
+        cli.config.presets.insert("ci".to_string(), vec!["--json".to_string()]);
+        assert_eq!(cli.preset_args("ci").unwrap(), ["--json"]);
+
+        // the preset's flags come first, explicit ones replace them
+        let cli = Cli::parse_from(["rskill", "--older-than", "30", "--older-than", "90"]);
+        assert_eq!(cli.older_than, Some(90));
+    }
+
+    #[test]
+    fn test_age_filters_select_a_window() {
+        let window = Cli::parse_from(["rskill", "--older-than", "30", "--newer-than", "90"]);
+        assert!(!window.matches_age_filters(Some(29)));
+        assert!(window.matches_age_filters(Some(30)));
+        assert!(window.matches_age_filters(Some(89)));
+        assert!(!window.matches_age_filters(Some(90)));
+        assert!(!window.matches_age_filters(None));
+
+        let older = Cli::parse_from(["rskill", "--older-than", "30"]);
+        assert!(older.matches_age_filters(Some(365)));
+        assert!(!older.matches_age_filters(None));
+    }
+
+    #[test]
+    fn test_age_filters_keep_unknown_ages_when_unset() {
+        let cli = Cli::parse_from(["rskill"]);
+        assert!(cli.matches_age_filters(None));
+        assert!(cli.matches_age_filters(Some(0)));
+    }
+
+    #[test]
+    fn test_rebuild_after_conflicts_with_delete_all() {
+        assert!(Cli::try_parse_from(["rskill", "--rebuild-after", "--delete-all"]).is_err());
+        assert!(Cli::try_parse_from(["rskill", "--rebuild-after", "--here"]).is_ok());
+    }
+
+    #[test]
+    fn test_env_excludes_extend_cli_excludes() {
+        assert_eq!(
+            merge_excludes(Some("vendor:node_modules, build"), Some("old,")),
+            ["vendor", "node_modules", "build", "old"]
+        );
+        assert!(merge_excludes(None, None).is_empty());
     }
 }
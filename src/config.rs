@@ -0,0 +1,100 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use crate::{cli::FooterMetric, project::ArtifactType};
+
+/// persistent settings read from `<config dir>/rskill/config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// extra artifact directories cleaned alongside the `--target` directory
+    pub artifacts: Vec<ArtifactPattern>,
+    /// command run by `--rebuild-after`, `cargo build` when unset
+    pub rebuild_command: Option<String>,
+    /// metrics shown in the TUI footer, e.g. `["projects", "cleanable", "sort"]`
+    pub footer: Option<Vec<FooterMetric>>,
+    /// named flag sets for `--preset`, e.g. `weekly = ["--older-than", "7", "--bars"]`
+    pub presets: BTreeMap<String, Vec<String>>,
+}
+
+/// a project-relative directory pattern, e.g. `dist` or `target/wasm32-*`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArtifactPattern {
+    pub pattern: String,
+    #[serde(rename = "type", default = "default_artifact_type")]
+    pub artifact_type: ArtifactType,
+}
+
+fn default_artifact_type() -> ArtifactType {
+    ArtifactType::Target
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rskill").join("config.toml"))
+    }
+
+    /// load the config file, a missing file just means the defaults
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Invalid config file {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_artifact_patterns() {
+        let config: Config = toml::from_str(
+            r#"
+            [[artifacts]]
+            pattern = "dist"
+
+            [[artifacts]]
+            pattern = "target/wasm32-*"
+            type = "Examples"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.artifacts.len(), 2);
+        assert_eq!(config.artifacts[0].pattern, "dist");
+        assert!(matches!(config.artifacts[0].artifact_type, ArtifactType::Target));
+        assert!(matches!(config.artifacts[1].artifact_type, ArtifactType::Examples));
+    }
+
+    #[test]
+    fn test_parse_footer_metrics() {
+        let config: Config = toml::from_str(r#"footer = ["projects", "scan-time"]"#).unwrap();
+        assert_eq!(config.footer, Some(vec![FooterMetric::Projects, FooterMetric::ScanTime]));
+    }
+
+    #[test]
+    fn test_parse_presets() {
+        let config: Config = toml::from_str(
+            r#"
+            [presets]
+            weekly = ["--older-than", "7", "--bars"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.presets["weekly"], ["--older-than", "7", "--bars"]);
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys() {
+        assert!(toml::from_str::<Config>("artefacts = []").is_err());
+    }
+}
@@ -0,0 +1,33 @@
+use std::{io, path::PathBuf, process::ExitStatus};
+use thiserror::Error;
+
+/// errors returned by the scanner and the filesystem helpers
+#[derive(Debug, Error)]
+pub enum RskillError {
+    #[error("scan task failed: {0}")]
+    ScanFailed(#[from] tokio::task::JoinError),
+
+    #[error("failed to delete {}: {source}", path.display())]
+    DeleteFailed {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("{}: {reason}", path.display())]
+    InvalidManifest { path: PathBuf, reason: String },
+
+    #[error("permission denied: {}", path.display())]
+    PermissionDenied { path: PathBuf },
+
+    #[error("`{command}` exited with {status}")]
+    CommandFailed { command: String, status: ExitStatus },
+
+    #[error("failed to serialize output: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, RskillError>;
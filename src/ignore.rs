@@ -0,0 +1,120 @@
+use std::fs;
+use crate::utils;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// name of the per-directory file listing projects that must never be cleaned
+pub const IGNORE_FILE_NAME: &str = ".rskillignore";
+
+/// `.rskillignore` rules, loaded lazily from the scan root down to each project
+///
+/// each non-empty, non-comment line is a pattern matched against the project path
+/// relative to the directory holding the ignore file:
+/// - a pattern without `/` matches any path component (`reference-*`)
+/// - a pattern with `/` matches the whole relative path (`work/client-a/*`)
+/// - `.` (or an empty file) matches the directory the file lives in
+#[derive(Default)]
+pub struct IgnoreRules {
+    cache: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl IgnoreRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// check whether any `.rskillignore` between the search root and the project protects it
+    pub fn is_protected(&mut self, project_dir: &Path, search_dir: &Path) -> bool {
+        for dir in project_dir.ancestors() {
+            if !dir.starts_with(search_dir) {
+                break;
+            }
+
+            let Ok(relative) = project_dir.strip_prefix(dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+
+            if let Some(patterns) = self.load(dir) {
+                if patterns.iter().any(|pattern| Self::matches(pattern, &relative)) {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    fn load(&mut self, dir: &Path) -> Option<&Vec<String>> {
+        self.cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let content = fs::read_to_string(dir.join(IGNORE_FILE_NAME)).ok()?;
+                let mut patterns: Vec<String> = content
+                    .lines()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(|line| line.trim_start_matches('/').trim_end_matches('/').to_string())
+                    .collect();
+
+                // an empty ignore file protects the directory it sits in
+                if patterns.is_empty() {
+                    patterns.push(".".to_string());
+                }
+                Some(patterns)
+            })
+            .as_ref()
+    }
+
+    fn matches(pattern: &str, relative: &str) -> bool {
+        let path_parts: Vec<&str> = relative.split('/').filter(|p| !p.is_empty()).collect();
+
+        if pattern == "." || pattern.is_empty() {
+            return path_parts.is_empty();
+        }
+
+        if !pattern.contains('/') {
+            return path_parts.iter().any(|part| utils::matches_wildcard(pattern, part));
+        }
+
+        let pattern_parts: Vec<&str> = pattern.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+        pattern_parts.len() == path_parts.len()
+            && pattern_parts
+                .iter()
+                .zip(&path_parts)
+                .all(|(pattern, part)| utils::matches_wildcard(pattern, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_patterns() {
+        assert!(IgnoreRules::matches("reference-*", "work/reference-build"));
+        assert!(!IgnoreRules::matches("reference-*", "work/other"));
+        assert!(IgnoreRules::matches("work/*", "work/app"));
+        assert!(!IgnoreRules::matches("work/*", "work/app/nested"));
+        assert!(IgnoreRules::matches(".", ""));
+        assert!(!IgnoreRules::matches(".", "app"));
+    }
+
+    #[test]
+    fn test_root_and_project_files() -> anyhow::Result<()> {
+        let root = tempdir()?;
+        fs::create_dir_all(root.path().join("keep"))?;
+        fs::create_dir_all(root.path().join("local"))?;
+        fs::create_dir_all(root.path().join("clean"))?;
+        fs::write(root.path().join(IGNORE_FILE_NAME), "# reference builds\nkeep\n")?;
+        fs::write(root.path().join("local").join(IGNORE_FILE_NAME), "")?;
+
+        let mut rules = IgnoreRules::new();
+        assert!(rules.is_protected(&root.path().join("keep"), root.path()));
+        assert!(rules.is_protected(&root.path().join("local"), root.path()));
+        assert!(!rules.is_protected(&root.path().join("clean"), root.path()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+pub mod ui;
+pub mod archive;
+pub mod cli;
+pub mod error;
+pub mod utils;
+pub mod ignore;
+pub mod config;
+pub mod plan;
+pub mod report;
+pub mod snapshot;
+pub mod treemap;
+pub mod cleaner;
+pub mod scanner;
+pub mod project;
+pub mod units;
+
+/// process exit codes, listed in `--help`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    ScanError = 1,
+    DeleteFailed = 2,
+    NothingFound = 3,
+    Aborted = 4,
+    RebuildFailed = 5,
+}
@@ -1,29 +1,244 @@
-use anyhow::Result;
 use clap::Parser;
-
-mod ui;
-mod cli;
-mod utils;
-mod scanner;
-mod project;
-
-use cli::Cli;
-use scanner::ProjectScanner;
-use ui::InteractiveUI;
+use rskill::{
+    utils,
+    snapshot,
+    ExitCode,
+    cli::{self, Cli},
+    config::Config,
+    plan::DeletionPlan,
+    snapshot::Snapshot,
+    treemap::TreemapNode,
+    cleaner::BatchCleaner,
+    scanner::ProjectScanner,
+    ui::InteractiveUI,
+};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
     
+    let code = match run(cli).await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            ExitCode::ScanError
+        }
+    };
+    
+    std::process::exit(code as i32);
+}
+
+async fn run(mut cli: Cli) -> anyhow::Result<ExitCode> {
+    cli.config = Config::load()?;
+    if let Some(name) = cli.preset.clone() {
+        let Some(preset_args) = cli.preset_args(&name) else {
+            let custom: Vec<_> = cli.config.presets.keys().map(String::as_str).collect();
+            anyhow::bail!(
+                "unknown preset `{}`, available: {}",
+                name,
+                cli::BUILTIN_PRESETS.iter().copied().chain(custom).collect::<Vec<_>>().join(", ")
+            );
+        };
+
+        // re-parse with the preset's flags ahead of the explicit ones
+        let config = std::mem::take(&mut cli.config);
+        let mut args = std::env::args_os();
+        cli = Cli::parse_from(args.next().into_iter().chain(preset_args.into_iter().map(Into::into)).chain(args));
+        cli.config = config;
+    }
+    colored::control::set_override(cli.use_color());
+    if cli.peek_archives && !cfg!(feature = "archives") {
+        anyhow::bail!("--peek-archives needs rskill built with the `archives` feature");
+    }
     let scanner = ProjectScanner::new(cli.clone());
     
-    if cli.list_only {
-        let projects = scanner.scan().await?;
-        scanner.print_projects(&projects).await?;
+    if let Some(plan_file) = &cli.apply {
+        // a plan is applied without scanning
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = DeletionPlan::load(plan_file).and_then(|plan| cleaner.apply_plan(&plan));
+        finish_with_report(&cleaner, &cli, result)
+    } else if let Some(plan_file) = &cli.plan {
+        let (projects, stats) = scanner.scan_with_stats().await?;
+        scanner.print_scan_stats(&stats);
+        let projects = scanner.take_top(projects);
+        let plan = DeletionPlan::from_projects(&projects, cli.disk_usage, cli.clean_scope())?;
+        plan.save(plan_file)?;
+        println!(
+            "Wrote plan for {} directories ({}) to {}",
+            plan.entries.len(),
+            utils::format_size(plan.total_size(), cli.gb),
+            plan_file.display()
+        );
+        if plan.entries.is_empty() {
+            return Ok(ExitCode::NothingFound);
+        }
+        Ok(ExitCode::Success)
+    } else if cli.snapshot.is_some() || cli.compare.is_some() {
+        let (projects, stats) = scanner.scan_with_stats().await?;
+        scanner.print_scan_stats(&stats);
+        let current = Snapshot::new(&projects)?;
+        
+        // load before saving, both may name the same file to roll it forward
+        if let Some(compare_file) = &cli.compare {
+            snapshot::print_comparison(&Snapshot::load(compare_file)?, &current, cli.gb, &cli.paths);
+        }
+        if let Some(snapshot_file) = &cli.snapshot {
+            current.save(snapshot_file)?;
+            eprintln!("Saved snapshot of {} projects to {}", projects.len(), snapshot_file.display());
+        }
+        Ok(ExitCode::Success)
+    } else if let Some(treemap_file) = &cli.treemap {
+        let (projects, stats) = scanner.scan_with_stats().await?;
+        scanner.print_scan_stats(&stats);
+        let projects = scanner.take_top(projects);
+        let treemap = TreemapNode::from_projects(&cli.get_search_directory(), &projects, &cli.paths);
+        treemap.save(treemap_file)?;
+        eprintln!(
+            "Wrote treemap of {} projects ({}) to {}",
+            treemap.children.len(),
+            utils::format_size(treemap.total(), cli.gb),
+            treemap_file.display()
+        );
+        if treemap.children.is_empty() {
+            return Ok(ExitCode::NothingFound);
+        }
+        Ok(ExitCode::Success)
+    } else if cli.here {
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = clean_here(&cli, &scanner, &cleaner).await;
+        finish_with_report(&cleaner, &cli, result)
+    } else if cli.dedupe_cargo_cache {
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = cleaner.dedupe_cargo_cache();
+        finish_with_report(&cleaner, &cli, result)
+    } else if let Some(goal) = cli.free {
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = async {
+            let (projects, stats) = scanner.scan_with_stats().await?;
+            scanner.print_scan_stats(&stats);
+            cleaner.record_scanned(projects.len());
+            if projects.is_empty() {
+                println!("No Rust projects found.");
+                return Ok(ExitCode::NothingFound);
+            }
+            cleaner.free_space(&projects, goal)
+        }
+        .await;
+        finish_with_report(&cleaner, &cli, result)
+    } else if cli.clean_incremental_only || cli.clean_docs_only || cli.clean_deps_only {
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = async {
+            let projects = scanner.scan().await?;
+            cleaner.record_scanned(projects.len());
+            if projects.is_empty() {
+                println!("No Rust projects found.");
+                return Ok(ExitCode::NothingFound);
+            }
+            if cli.clean_docs_only {
+                cleaner.clean_docs(&projects)
+            } else if cli.clean_deps_only {
+                cleaner.clean_deps(&projects)
+            } else {
+                cleaner.clean_incremental(&projects)
+            }
+        }
+        .await;
+        finish_with_report(&cleaner, &cli, result)
+    } else if cli.prune_empty || (cli.delete_all && cli.list_only) {
+        let cleaner = BatchCleaner::new(cli.clone());
+        let result = async {
+            let (projects, stats) = scanner.scan_with_stats().await?;
+            scanner.print_scan_stats(&stats);
+            let mut projects = scanner.take_top(projects);
+            cleaner.record_scanned(projects.len());
+            if projects.is_empty() {
+                println!("No Rust projects found.");
+                return Ok(ExitCode::NothingFound);
+            }
+
+            if cli.prune_empty {
+                let code = cleaner.prune_empty_targets(&mut projects)?;
+                if code != ExitCode::Success || !(cli.delete_all && cli.list_only) {
+                    return Ok(code);
+                }
+            }
+            cleaner.clean_all(&projects, cli.confirm)
+        }
+        .await;
+        finish_with_report(&cleaner, &cli, result)
+    } else if cli.jsonl {
+        if scanner.stream_projects_jsonl().await? == 0 {
+            return Ok(ExitCode::NothingFound);
+        }
+        Ok(ExitCode::Success)
+    } else if cli.list_only || cli.json {
+        list_projects(&cli, &scanner).await
+    } else if !InteractiveUI::terminal_available() {
+        eprintln!("No interactive terminal, listing the projects instead (--list-only skips this notice)");
+        list_projects(&cli, &scanner).await
+    } else if cli.peek_archives {
+        anyhow::bail!("--peek-archives reports below the project list, add --list-only");
     } else {
         let mut ui = InteractiveUI::new(cli.clone());
-        ui.run().await?;
+        ui.run().await
+    }
+}
+
+/// `--here`: clean the project in the search directory, then rebuild it if asked to
+async fn clean_here(cli: &Cli, scanner: &ProjectScanner, cleaner: &BatchCleaner) -> anyhow::Result<ExitCode> {
+    let project_dir = cli.get_search_directory();
+    if !project_dir.join("Cargo.toml").exists() {
+        eprintln!("No Cargo.toml in {}", project_dir.display());
+        return Ok(ExitCode::NothingFound);
+    }
+    let project = scanner.rescan_project(&project_dir).await?;
+    cleaner.record_scanned(1);
+    let code = cleaner.clean_all(&[project], true)?;
+    if code == ExitCode::Success && cli.rebuild_after && !cli.dry_run {
+        println!("Running `{}`...", cli.rebuild_command());
+        if !utils::run_command_in(&project_dir, cli.rebuild_command())? {
+            eprintln!("`{}` failed", cli.rebuild_command());
+            return Ok(ExitCode::RebuildFailed);
+        }
+    }
+    Ok(code)
+}
+
+/// with `--report-json`, write the report however the clean ended, a failed run lists
+/// its error against the search directory
+fn finish_with_report(cleaner: &BatchCleaner, cli: &Cli, result: anyhow::Result<ExitCode>) -> anyhow::Result<ExitCode> {
+    if let Err(err) = &result {
+        cleaner.record_error(&cli.get_search_directory(), format!("{:#}", err));
+    }
+    let saved = cleaner.save_report();
+    let code = result?;
+    saved?;
+    Ok(code)
+}
+
+/// list mode: scan, then print the table (or JSON)
+async fn list_projects(cli: &Cli, scanner: &ProjectScanner) -> anyhow::Result<ExitCode> {
+    let (projects, stats) = scanner.scan_with_stats().await?;
+    scanner.print_scan_stats(&stats);
+    let found_count = projects.len();
+    let projects = scanner.take_top(projects);
+    
+    if cli.json {
+        scanner.print_projects_json(&projects)?;
+    } else {
+        scanner.print_projects(&projects).await?;
     }
     
-    Ok(())
+    if projects.len() < found_count {
+        eprintln!("Showing top {} of {} projects", projects.len(), found_count);
+    }
+
+    if cli.profile {
+        stats.profile.print();
+    }
+    
+    if projects.is_empty() {
+        return Ok(ExitCode::NothingFound);
+    }
+    Ok(ExitCode::Success)
 }
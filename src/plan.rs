@@ -0,0 +1,159 @@
+use std::{fs, path::{Path, PathBuf}};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::{project::{CleanScope, RustProject}, utils};
+
+/// how much a directory may grow between `--plan` and `--apply` before it's skipped
+const ALLOWED_GROWTH_PERCENT: u64 = 10;
+
+/// directories to delete, written by `--plan` and executed by `--apply`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeletionPlan {
+    pub created: DateTime<Utc>,
+    pub entries: Vec<PlanEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlanEntry {
+    pub project: String,
+    pub project_path: PathBuf,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// outcome of re-verifying a planned entry
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlanCheck {
+    Ready,
+    Missing,
+    Grown { now: u64 },
+}
+
+impl DeletionPlan {
+    /// every cleanable directory of the unprotected projects, sized now so the
+    /// plan records what was approved
+    pub fn from_projects(projects: &[RustProject], disk_usage: bool, scope: CleanScope) -> Result<Self> {
+        let mut entries = Vec::new();
+        for project in projects.iter().filter(|p| !p.protected) {
+            // absolute paths, the plan may be applied from another working directory
+            let project_path = std::path::absolute(&project.path)?;
+            for dir in project.cleanable_dirs(scope) {
+                let path = std::path::absolute(&dir)?;
+                entries.push(PlanEntry {
+                    project: project.name.clone(),
+                    project_path: project_path.clone(),
+                    size: utils::calculate_dir_size(&path, disk_usage)?,
+                    path,
+                });
+            }
+        }
+
+        Ok(Self { created: Utc::now(), entries })
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.size).sum()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write plan {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read plan {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid plan {}", path.display()))
+    }
+}
+
+impl PlanEntry {
+    /// the entry lies inside its project, within one of the project's `artifact_dirs`, and
+    /// no directory on the way is a symlink that would lead the deletion elsewhere
+    pub fn is_inside_artifacts(&self, artifact_dirs: &[PathBuf]) -> bool {
+        let project = utils::normalize_path(&self.project_path);
+        let path = utils::normalize_path(&self.path);
+        if !project.join("Cargo.toml").is_file() || !path.starts_with(&project) || path == project {
+            return false;
+        }
+        let in_artifacts = artifact_dirs
+            .iter()
+            .map(|dir| utils::normalize_path(dir))
+            .any(|dir| dir.starts_with(&project) && dir != project && path.starts_with(&dir));
+
+        in_artifacts
+            && path
+                .ancestors()
+                .skip(1)
+                .take_while(|dir| *dir != project)
+                .all(|dir| !fs::symlink_metadata(dir).is_ok_and(|m| m.file_type().is_symlink()))
+    }
+
+    /// re-verify the entry right before deleting it
+    pub fn check(&self, disk_usage: bool) -> Result<PlanCheck> {
+        if !self.path.exists() {
+            return Ok(PlanCheck::Missing);
+        }
+
+        let now = utils::calculate_dir_size(&self.path, disk_usage)?;
+        if now > self.size + self.size * ALLOWED_GROWTH_PERCENT / 100 {
+            return Ok(PlanCheck::Grown { now });
+        }
+        Ok(PlanCheck::Ready)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_detects_missing_and_grown() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let target = temp_dir.path().join("target");
+        fs::create_dir(&target)?;
+        fs::write(target.join("a.bin"), vec![0u8; 100])?;
+
+        let entry = PlanEntry {
+            project: "app".to_string(),
+            project_path: temp_dir.path().to_path_buf(),
+            path: target.clone(),
+            size: 100,
+        };
+        assert_eq!(entry.check(false)?, PlanCheck::Ready);
+
+        fs::write(target.join("b.bin"), vec![0u8; 50])?;
+        assert_eq!(entry.check(false)?, PlanCheck::Grown { now: 150 });
+
+        fs::remove_dir_all(&target)?;
+        assert_eq!(entry.check(false)?, PlanCheck::Missing);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_entries_must_sit_in_an_artifact_dir_of_their_project() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project_path = temp_dir.path().join("app");
+        fs::create_dir_all(project_path.join("target/debug"))?;
+        fs::write(project_path.join("Cargo.toml"), "[package]\nname = \"app\"\n")?;
+        let artifact_dirs = [project_path.join("target")];
+        let entry = |path: PathBuf| PlanEntry {
+            project: "app".to_string(),
+            project_path: project_path.clone(),
+            path,
+            size: 0,
+        };
+
+        assert!(entry(project_path.join("target")).is_inside_artifacts(&artifact_dirs));
+        assert!(entry(project_path.join("target/debug")).is_inside_artifacts(&artifact_dirs));
+        assert!(!entry(project_path.join("src")).is_inside_artifacts(&artifact_dirs));
+        assert!(!entry(project_path.clone()).is_inside_artifacts(&artifact_dirs));
+        assert!(!entry(project_path.join("target/../../other/target")).is_inside_artifacts(&artifact_dirs));
+        assert!(!entry(temp_dir.path().join("other/target")).is_inside_artifacts(&artifact_dirs));
+
+        Ok(())
+    }
+}
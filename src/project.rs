@@ -1,19 +1,66 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use crate::utils;
+
+/// projects untouched for this many days are reported as stale
+pub const DEFAULT_STALE_DAYS: i64 = 30;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RustProject {
     pub path: PathBuf,
     pub name: String,
+    pub version: Option<String>,
+    /// `None` when neither the manifest nor the layout reveal a target
+    pub crate_kind: Option<CrateKind>,
     pub target_dir: Option<PathBuf>,
+    /// the target directory is a symlink, e.g. to a ramdisk or a shared cache; removing
+    /// it only removes the link
+    pub target_symlink: bool,
+    /// the target lies outside the project (`build.target-dir`, `CARGO_TARGET_DIR`) and
+    /// other projects build into it too, it's never deleted through this project
+    pub shared_target: bool,
     pub target_size: u64,
+    /// false when sizing was skipped with `--no-sizes`, sizes are then reported as unknown
+    pub size_known: bool,
+    /// sizing stopped at `--size-cap`, the target is at least `target_size`
+    pub size_capped: bool,
+    /// biggest single file in the target directory, found with `--largest-file`
+    pub largest_file: Option<LargestFile>,
     pub last_modified: Option<DateTime<Utc>>,
+    /// newest cargo fingerprint under `target/[<triple>/]<profile>/.fingerprint`, i.e.
+    /// when the target was last built
+    pub last_build: Option<DateTime<Utc>>,
     pub workspace_root: bool,
+    pub virtual_manifest: bool,
+    pub workspace_members: usize,
     pub has_lock_file: bool,
     pub dependencies_count: usize,
+    pub workspace_dependencies_count: usize,
     pub build_artifacts: Vec<BuildArtifact>,
     pub cargo_cache_size: u64,
+    /// directories matched by the config's artifact patterns, outside the target directory
+    pub extra_artifacts: Vec<PathBuf>,
+    pub extra_artifacts_size: u64,
+    /// matched by a `.rskillignore`, listed but never deleted
+    pub protected: bool,
+    /// lives inside a git repository
+    pub git_repo: bool,
+    /// main working tree of that repository, the same for all of its worktrees
+    pub git_repository: Option<PathBuf>,
+    /// filesystem of the project directory and of its target directory, they differ
+    /// when the target is a mount point or a symlink to another disk
+    pub device: Option<u64>,
+    pub target_device: Option<u64>,
+}
+
+/// a single file dominating a target, e.g. a test binary with full debug info
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LargestFile {
+    pub path: PathBuf,
+    pub size: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,9 +69,11 @@ pub struct BuildArtifact {
     pub artifact_type: ArtifactType,
     pub size: u64,
     pub last_modified: Option<DateTime<Utc>>,
+    /// finer-grained description, e.g. which part of the cargo cache this is
+    pub label: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ArtifactType {
     Target,
     IncrementalCompilation,
@@ -32,22 +81,352 @@ pub enum ArtifactType {
     Examples,
     Tests,
     Benchmarks,
+    Documentation,
     CargoRegistry,
     CargoGitCache,
     CargoConfigCache,
+    RustupToolchain,
+    RustupDownloads,
+    /// a `cargo vendor` directory the project's cargo config builds from
+    VendoredDeps,
+}
+
+/// which of a project's artifacts a clean removes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CleanScope {
+    /// leave config artifacts that aren't regenerable build output alone
+    pub safe_only: bool,
+    /// keep the generated documentation under `target/[<triple>/]doc`
+    pub keep_docs: bool,
+    /// leave target directories that are symlinks alone
+    pub skip_symlinked_targets: bool,
+}
+
+/// which targets a package builds, virtual workspace roots build none of their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CrateKind {
+    Bin,
+    Lib,
+    Both,
+    Workspace,
+}
+
+impl CrateKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            CrateKind::Bin => "bin",
+            CrateKind::Lib => "lib",
+            CrateKind::Both => "bin+lib",
+            CrateKind::Workspace => "workspace",
+        }
+    }
+}
+
+/// what the list's status column says about a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectStatus {
+    Active,
+    Stale,
+    EmptyTarget,
+    NoTarget,
+    Protected,
+    Workspace,
+}
+
+impl ProjectStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectStatus::Active => "Active",
+            ProjectStatus::Stale => "Stale",
+            ProjectStatus::EmptyTarget => "Empty target",
+            ProjectStatus::NoTarget => "No target",
+            ProjectStatus::Protected => "Protected",
+            ProjectStatus::Workspace => "Workspace",
+        }
+    }
+}
+
+/// rough guess at how long rebuilding after a clean takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebuildCost {
+    Cheap,
+    Moderate,
+    Expensive,
+}
+
+impl RebuildCost {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RebuildCost::Cheap => "cheap",
+            RebuildCost::Moderate => "moderate",
+            RebuildCost::Expensive => "expensive",
+        }
+    }
 }
 
+/// whether the sources changed since the target was last built
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildFreshness {
+    /// sources are newer than the last build, the next build recompiles anyway
+    Stale,
+    /// the target matches the sources, cleaning throws away a good build
+    Fresh,
+}
+
+impl BuildFreshness {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BuildFreshness::Stale => "stale build",
+            BuildFreshness::Fresh => "fresh build",
+        }
+    }
+}
+
+/// compiled output above this size hints at heavy (often native) dependencies (2 GB)
+const HEAVY_TARGET_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+#[cfg(test)]
 impl RustProject {
+    /// a project at `path` with a target of `target_size` bytes and nothing else found,
+    /// for tests that don't need a tree on disk
+    pub(crate) fn fixture(path: &str, target_size: u64) -> Self {
+        let path = PathBuf::from(path);
+        Self {
+            name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            path,
+            version: None,
+            crate_kind: None,
+            target_dir: None,
+            target_symlink: false,
+            shared_target: false,
+            target_size,
+            size_known: true,
+            size_capped: false,
+            largest_file: None,
+            last_modified: None,
+            last_build: None,
+            workspace_root: false,
+            virtual_manifest: false,
+            workspace_members: 0,
+            has_lock_file: false,
+            dependencies_count: 0,
+            workspace_dependencies_count: 0,
+            build_artifacts: Vec::new(),
+            cargo_cache_size: 0,
+            extra_artifacts: Vec::new(),
+            extra_artifacts_size: 0,
+            protected: false,
+            git_repo: false,
+            git_repository: None,
+            device: None,
+            target_device: None,
+        }
+    }
+}
+
+impl RustProject {
+    /// target and extra artifacts, the cargo cache is shared by every project and added to
+    /// totals once
     pub fn total_cleanable_size(&self) -> u64 {
-        self.target_size + self.cargo_cache_size
+        self.target_size + self.extra_artifacts_size
+    }
+
+    /// bytes per artifact type found in the project
+    pub fn artifact_sizes(&self) -> BTreeMap<ArtifactType, u64> {
+        let mut sizes = BTreeMap::new();
+        for artifact in &self.build_artifacts {
+            *sizes.entry(artifact.artifact_type.clone()).or_default() += artifact.size;
+        }
+        sizes
+    }
+
+    /// every directory removed when the project is cleaned within `scope`
+    pub fn cleanable_dirs(&self, scope: CleanScope) -> Vec<PathBuf> {
+        let target_dirs = match &self.target_dir {
+            Some(_) if self.target_symlink && scope.skip_symlinked_targets => Vec::new(),
+            Some(_) if self.shared_target => Vec::new(),
+            // never reach through a symlink into what may be a shared cache
+            Some(target_dir) if scope.keep_docs && !self.target_symlink && Self::holds_docs(target_dir) => {
+                Self::dirs_besides_docs(target_dir)
+            }
+            Some(target_dir) => vec![target_dir.clone()],
+            None => Vec::new(),
+        };
+
+        target_dirs
+            .into_iter()
+            .chain(
+                self.extra_artifacts
+                    .iter()
+                    .filter(|dir| !scope.safe_only || self.is_safe_artifact(dir))
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    /// bytes freed by cleaning within `scope`, unlinking a symlinked target frees nothing
+    /// and a shared target isn't deleted at all
+    pub fn cleanable_size(&self, scope: CleanScope) -> u64 {
+        let kept_target = if self.target_symlink || self.shared_target { self.target_size } else { 0 };
+        let kept_docs = if scope.keep_docs && !self.target_symlink {
+            self.artifact_size(&ArtifactType::Documentation)
+        } else {
+            0
+        };
+        let kept_extras: u64 = if scope.safe_only {
+            self.build_artifacts
+                .iter()
+                .filter(|a| self.extra_artifacts.contains(&a.path) && !a.artifact_type.is_safe_to_delete())
+                .map(|a| a.size)
+                .sum()
+        } else {
+            0
+        };
+        // the cargo cache is measured but never deleted with a project
+        (self.target_size + self.extra_artifacts_size).saturating_sub(kept_target + kept_docs + kept_extras)
+    }
+
+    fn artifact_size(&self, artifact_type: &ArtifactType) -> u64 {
+        self.build_artifacts
+            .iter()
+            .filter(|a| a.artifact_type == *artifact_type)
+            .map(|a| a.size)
+            .sum()
+    }
+
+    /// checked on disk, sizes and with them the classified artifacts may be skipped
+    fn holds_docs(target_dir: &Path) -> bool {
+        target_dir.join("doc").is_dir()
+            || fs::read_dir(target_dir)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .any(|entry| entry.path().join("doc").is_dir())
+    }
+
+    /// subdirectories of `dir` except `doc`, descending into the target triples that
+    /// hold documentation of their own
+    fn dirs_besides_docs(dir: &Path) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if !path.is_dir() || entry.file_name() == "doc" {
+                continue;
+            }
+            if path.join("doc").is_dir() {
+                dirs.extend(Self::dirs_besides_docs(&path));
+            } else {
+                dirs.push(path);
+            }
+        }
+        dirs.sort();
+        dirs
+    }
+
+    /// whether `dir` is the project's vendored dependencies, which builds need back
+    /// (`cargo vendor`) before they work again
+    pub fn is_vendored_deps(&self, dir: &Path) -> bool {
+        self.build_artifacts
+            .iter()
+            .any(|a| a.path == dir && a.artifact_type == ArtifactType::VendoredDeps)
+    }
+
+    /// an artifact of unknown type counts as unsafe
+    fn is_safe_artifact(&self, dir: &Path) -> bool {
+        self.build_artifacts
+            .iter()
+            .find(|a| a.path == dir)
+            .is_some_and(|a| a.artifact_type.is_safe_to_delete())
+    }
+
+    /// name shown in listings, virtual workspace roots are labelled as such
+    pub fn display_name(&self) -> String {
+        let name = utils::escape_control(&self.name);
+        let mut name = if self.virtual_manifest {
+            format!(
+                "{} [workspace root (virtual), {} members]",
+                name, self.workspace_members
+            )
+        } else {
+            name
+        };
+
+        if self.protected {
+            name.push_str(" (protected)");
+        }
+        name
     }
 
     pub fn format_size(&self, use_gb: bool) -> String {
+        if !self.size_known {
+            return "unknown".to_string();
+        }
+
         let size = self.total_cleanable_size();
+        let at_least = if self.size_capped { "≥ " } else { "" };
         if use_gb {
-            format!("{:.2} GB", size as f64 / (1024.0 * 1024.0 * 1024.0))
+            format!("{}{:.2} GB", at_least, size as f64 / (1024.0 * 1024.0 * 1024.0))
         } else {
-            format!("{:.2} MB", size as f64 / (1024.0 * 1024.0))
+            format!("{}{:.2} MB", at_least, size as f64 / (1024.0 * 1024.0))
+        }
+    }
+
+    /// size column text, telling apart projects without a target from already-clean ones
+    pub fn size_label(&self, use_gb: bool) -> String {
+        if self.size_known && self.total_cleanable_size() == 0 {
+            if self.target_dir.is_none() {
+                return "no target".to_string();
+            }
+            return "empty target".to_string();
+        }
+
+        self.format_size(use_gb)
+    }
+
+    pub fn target_on_other_filesystem(&self) -> bool {
+        self.target_device.is_some() && self.target_device != self.device
+    }
+
+    /// reclaimable bytes per filesystem, the target counts towards its own filesystem
+    pub fn reclaimable_by_device(&self) -> Vec<(Option<u64>, u64)> {
+        let rest = self.total_cleanable_size() - self.target_size;
+        if self.target_on_other_filesystem() {
+            vec![(self.target_device, self.target_size), (self.device, rest)]
+        } else {
+            vec![(self.device, self.total_cleanable_size())]
+        }
+    }
+
+    /// estimate from the dependency count, bumped a level when the compiled
+    /// dependencies are unusually large; a target without compiled dependencies is cheap
+    pub fn rebuild_cost(&self) -> RebuildCost {
+        let has_compiled_deps = self
+            .build_artifacts
+            .iter()
+            .any(|a| matches!(a.artifact_type, ArtifactType::Dependencies));
+        if self.size_known && self.target_dir.is_some() && !has_compiled_deps {
+            return RebuildCost::Cheap;
+        }
+
+        let cost = match self.dependencies_count {
+            0..20 => RebuildCost::Cheap,
+            20..100 => RebuildCost::Moderate,
+            _ => RebuildCost::Expensive,
+        };
+
+        match cost {
+            RebuildCost::Cheap if self.target_size > HEAVY_TARGET_SIZE => RebuildCost::Moderate,
+            RebuildCost::Moderate if self.target_size > HEAVY_TARGET_SIZE => RebuildCost::Expensive,
+            cost => cost,
+        }
+    }
+
+    /// `None` without a build fingerprint or known source times to compare
+    pub fn build_freshness(&self) -> Option<BuildFreshness> {
+        match (self.last_modified, self.last_build) {
+            (Some(sources), Some(build)) if sources > build => Some(BuildFreshness::Stale),
+            (Some(_), Some(_)) => Some(BuildFreshness::Fresh),
+            _ => None,
         }
     }
 
@@ -58,9 +437,33 @@ impl RustProject {
         })
     }
 
-    pub fn is_likely_active(&self) -> bool {
+    /// the most telling status first: protection and a missing or empty target matter more
+    /// than how recently the sources changed
+    pub fn status(&self, stale_days: i64) -> ProjectStatus {
+        if self.protected {
+            ProjectStatus::Protected
+        } else if self.target_dir.is_none() {
+            ProjectStatus::NoTarget
+        } else if self.size_known && self.target_size == 0 {
+            ProjectStatus::EmptyTarget
+        } else if self.workspace_root {
+            ProjectStatus::Workspace
+        } else if self.is_likely_active(stale_days) {
+            ProjectStatus::Active
+        } else {
+            ProjectStatus::Stale
+        }
+    }
+
+    /// git reports changes outside the target, a hint the project is still being worked on
+    pub fn has_uncommitted_changes(&self) -> bool {
+        self.git_repo && utils::has_uncommitted_changes(&self.path, self.target_dir.as_deref()) == Some(true)
+    }
+
+    /// modified within the last `stale_days` days
+    pub fn is_likely_active(&self, stale_days: i64) -> bool {
         self.days_since_modified()
-            .map(|days| days < 30) // Modified within last 30 days
+            .map(|days| days < stale_days)
             .unwrap_or(true) // If we can't determine, assume active for safety
     }
 }
@@ -74,23 +477,50 @@ impl ArtifactType {
             ArtifactType::Examples => "Compiled examples",
             ArtifactType::Tests => "Compiled tests",
             ArtifactType::Benchmarks => "Compiled benchmarks",
+            ArtifactType::Documentation => "Generated documentation",
             ArtifactType::CargoRegistry => "Cargo registry cache",
             ArtifactType::CargoGitCache => "Cargo git cache",
             ArtifactType::CargoConfigCache => "Cargo configuration cache",
+            ArtifactType::RustupToolchain => "Installed rustup toolchain",
+            ArtifactType::RustupDownloads => "Rustup download cache",
+            ArtifactType::VendoredDeps => "Vendored dependencies",
+        }
+    }
+
+    /// what deleting this kind of artifact costs on the next build
+    pub fn rebuild_consequence(&self) -> &'static str {
+        match self {
+            ArtifactType::IncrementalCompilation => "fast to rebuild, only your own crates lose their incremental state",
+            ArtifactType::Dependencies => "slow to rebuild, every dependency is compiled again on the next build",
+            ArtifactType::Target => "slow to rebuild, the next build starts from scratch",
+            ArtifactType::Examples | ArtifactType::Tests | ArtifactType::Benchmarks => {
+                "cheap to rebuild, relinked from the compiled dependencies"
+            }
+            ArtifactType::Documentation => "no rebuild, regenerated by the next `cargo doc`",
+            ArtifactType::CargoRegistry | ArtifactType::CargoGitCache | ArtifactType::CargoConfigCache => {
+                "downloaded again by the next build that needs it"
+            }
+            ArtifactType::RustupToolchain => "reinstalled by rustup, a large download",
+            ArtifactType::RustupDownloads => "nothing to rebuild",
+            ArtifactType::VendoredDeps => "builds fail until `cargo vendor` runs again",
         }
     }
 
-    pub fn _is_safe_to_delete(&self) -> bool {
+    pub fn is_safe_to_delete(&self) -> bool {
         match self {
             ArtifactType::Target
             | ArtifactType::IncrementalCompilation
             | ArtifactType::Dependencies
             | ArtifactType::Examples
             | ArtifactType::Tests
-            | ArtifactType::Benchmarks => true,
+            | ArtifactType::Benchmarks
+            | ArtifactType::Documentation => true,
             ArtifactType::CargoRegistry
             | ArtifactType::CargoGitCache
-            | ArtifactType::CargoConfigCache => false, // More global, need warning
+            | ArtifactType::CargoConfigCache
+            | ArtifactType::RustupToolchain
+            | ArtifactType::RustupDownloads
+            | ArtifactType::VendoredDeps => false, // More global, need warning
         }
     }
 }
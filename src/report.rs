@@ -0,0 +1,63 @@
+use std::{collections::HashSet, fs, path::{Path, PathBuf}};
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// the outcome of a delete run, written by `--report-json` for CI to assert on
+#[derive(Debug, Default, Serialize)]
+pub struct DeleteReport {
+    pub projects_scanned: usize,
+    pub projects_deleted: usize,
+    pub bytes_reclaimed: u64,
+    pub errors: Vec<ReportError>,
+    pub duration_ms: u64,
+    pub dry_run: bool,
+    /// projects already counted in `projects_deleted`
+    #[serde(skip)]
+    deleted: HashSet<PathBuf>,
+}
+
+/// a directory that couldn't be cleaned, and why
+#[derive(Debug, Serialize)]
+pub struct ReportError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl DeleteReport {
+    /// a project counts once however many of its directories were deleted
+    pub fn record_deleted(&mut self, project: &Path, size: u64) {
+        if self.deleted.insert(project.to_path_buf()) {
+            self.projects_deleted += 1;
+        }
+        self.bytes_reclaimed += size;
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write report {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projects_are_counted_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = DeleteReport::default();
+        report.record_deleted(Path::new("/src/app"), 0);
+        report.record_deleted(Path::new("/src/app"), 300);
+        report.record_deleted(Path::new("/src/lib"), 200);
+        report.errors.push(ReportError { path: "/src/old".into(), message: "a build is in progress".to_string() });
+
+        let path = dir.path().join("report.json");
+        report.save(&path).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(json["projects_deleted"], 2);
+        assert_eq!(json["bytes_reclaimed"], 500);
+        assert_eq!(json["errors"][0]["path"], "/src/old");
+        assert!(json.get("deleted").is_none());
+    }
+}
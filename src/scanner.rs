@@ -1,92 +1,486 @@
 use std::fs;
-use tokio::task;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use serde::Serialize;
+use tokio::task::{self, JoinHandle};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 use crate::utils;
-use anyhow::Result;
-use std::path::Path;
+use crate::ignore::IgnoreRules;
+use crate::archive::{self, ArchiveSummary};
+use crate::error::{Result, RskillError};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use walkdir::WalkDir;
 use colored::Colorize;
 use chrono::{DateTime, Utc};
-use crate::cli::{Cli, SortBy};
-use spinoff::{spinners, Spinner};
-use crate::project::{ArtifactType, BuildArtifact, RustProject};
+use crate::cli::{Cli, SortBy, TimestampFormat};
+use spinoff::{spinners, Spinner, Streams};
+use crate::project::{ArtifactType, BuildArtifact, BuildFreshness, CrateKind, ProjectStatus, RustProject, DEFAULT_STALE_DAYS};
 
 pub struct ProjectScanner {
     cli: Cli,
 }
 
+/// how deep source directories are walked when looking for the newest file
+const SOURCE_MTIME_DEPTH: usize = 4;
+
+/// age buckets of the summary histogram, each holding projects modified less than that
+/// many days ago (the last one catches the rest)
+const AGE_BUCKETS: [(&str, i64); 4] = [("< 7d", 7), ("7-30d", 30), ("30-90d", 90), ("90d+", i64::MAX)];
+
+/// `--target`'s default, replaced by a cargo config's `build.target-dir`
+const DEFAULT_TARGET_NAME: &str = "target";
+
+/// a directory needs this many entries before `--exclude` is suggested for it
+const EXCLUDE_TIP_MIN_ENTRIES: u64 = 10_000;
+
+/// timing and throughput of a single scan
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    pub directories_scanned: usize,
+    pub projects_found: usize,
+    pub elapsed: Duration,
+    /// top-level subtree of the search root that took the longest to walk
+    pub slowest_subtree: Option<(PathBuf, Duration)>,
+    /// entries seen by the walk, below the search root
+    pub entries_walked: u64,
+    /// directory holding the most entries without holding a project or being a target,
+    /// with its entry count
+    pub heaviest_subtree: Option<(PathBuf, u64)>,
+    /// the walk was cut short by `--timeout`, the projects found are incomplete
+    pub timed_out: bool,
+    /// manifests rejected by `--verify-cargo-toml`, with the reason
+    pub skipped_manifests: Vec<(PathBuf, String)>,
+    /// archives holding crates, found with `--peek-archives`
+    pub archives: Vec<ArchiveSummary>,
+    pub profile: ScanProfile,
+}
+
+/// time spent in each scan stage, reported with `--profile`
+#[derive(Debug, Clone, Default)]
+pub struct ScanProfile {
+    /// walking the tree, excluding the time spent analyzing projects
+    pub walk: Duration,
+    pub parse: Duration,
+    pub sizing: Duration,
+    pub sort: Duration,
+    /// sizing time of every analyzed project
+    pub project_sizing: Vec<(PathBuf, Duration)>,
+}
+
+impl ScanProfile {
+    pub fn print(&self) {
+        eprintln!("Scan profile:");
+        eprintln!("  directory walk  {:>8.3}s", self.walk.as_secs_f64());
+        eprintln!("  manifest parse  {:>8.3}s", self.parse.as_secs_f64());
+        eprintln!("  size calculation{:>8.3}s", self.sizing.as_secs_f64());
+        eprintln!("  sort            {:>8.3}s", self.sort.as_secs_f64());
+
+        let mut slowest: Vec<_> = self.project_sizing.iter().collect();
+        slowest.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        if !slowest.is_empty() {
+            eprintln!("  slowest to size:");
+        }
+        for (path, time) in slowest.into_iter().take(5) {
+            eprintln!("    {:>8.3}s  {}", time.as_secs_f64(), path.display());
+        }
+    }
+}
+
+impl ScanStats {
+    pub fn summary(&self) -> String {
+        format!(
+            "Scanned {} directories, found {} projects in {:.1}s",
+            self.directories_scanned,
+            self.projects_found,
+            self.elapsed.as_secs_f64()
+        )
+    }
+}
+
+/// a project as printed by `--json` and `--jsonl`
+#[derive(Serialize)]
+struct JsonProject {
+    #[serde(flatten)]
+    project: RustProject,
+    artifact_sizes: BTreeMap<ArtifactType, u64>,
+}
+
+/// orders projects by cleanable size, for keeping the largest in a bounded heap
+struct BySize(RustProject);
+
+impl PartialEq for BySize {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for BySize {}
+
+impl PartialOrd for BySize {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BySize {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cleanable_size().cmp(&other.0.total_cleanable_size())
+    }
+}
+
 impl ProjectScanner {
     pub fn new(cli: Cli) -> Self {
         Self { cli }
     }
 
     pub async fn scan(&self) -> Result<Vec<RustProject>> {
+        Ok(self.scan_with_stats().await?.0)
+    }
+
+    pub async fn scan_with_stats(&self) -> Result<(Vec<RustProject>, ScanStats)> {
+        let started = Instant::now();
         let search_dir = self.cli.get_search_directory();
         let excluded_dirs = self.cli.get_excluded_dirs();
 
-        let spinner = Spinner::new(
+        let spinner = Spinner::new_with_stream(
             spinners::Dots,
             format!("Scanning for Rust projects in: {}", search_dir.display()),
             spinoff::Color::White,
+            Streams::Stderr,
         );
 
         let cli_clone = self.cli.clone();
-        let projects = task::spawn_blocking(move || {
-            Self::find_rust_projects(&search_dir, &excluded_dirs, &cli_clone)
+        let (projects, mut stats) = task::spawn_blocking(move || {
+            let mut stats = ScanStats::default();
+            let projects = Self::find_rust_projects(&search_dir, &excluded_dirs, &cli_clone, &mut stats, None)?;
+            Ok::<_, RskillError>((projects, stats))
         }).await??;
 
         spinner.clear();
+        if stats.timed_out {
+            eprintln!("{}", "Scan timed out, the results are incomplete".yellow());
+        }
 
-        Ok(projects)
+        stats.projects_found = projects.len();
+        stats.elapsed = started.elapsed();
+
+        Ok((projects, stats))
+    }
+
+    /// scan in the background, sending each project over the channel as soon as it's analyzed
+    ///
+    /// projects arrive unsorted, use [`ProjectScanner::sort`] once the returned handle completes;
+    /// `--max-results` isn't applied here, see [`ProjectScanner::take_top`]
+    pub fn scan_streaming(&self) -> (UnboundedReceiver<RustProject>, JoinHandle<Result<ScanStats>>) {
+        let started = Instant::now();
+        let search_dir = self.cli.get_search_directory();
+        let excluded_dirs = self.cli.get_excluded_dirs();
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let cli_clone = self.cli.clone();
+        let handle = task::spawn_blocking(move || {
+            let mut stats = ScanStats::default();
+            Self::find_rust_projects(
+                &search_dir,
+                &excluded_dirs,
+                &cli_clone,
+                &mut stats,
+                Some(&sender),
+            )?;
+            stats.elapsed = started.elapsed();
+            Ok(stats)
+        });
+
+        (receiver, handle)
+    }
+
+    /// sort projects according to CLI preferences
+    pub fn sort(&self, projects: &mut [RustProject]) {
+        Self::sort_for_cli(projects, &self.cli);
+    }
+
+    /// keep only the `--top` largest projects, preserving the configured sort order,
+    /// `--max-results` counts too since streamed scans see every project
+    pub fn take_top(&self, projects: Vec<RustProject>) -> Vec<RustProject> {
+        let Some(top) = [self.cli.top, self.cli.max_results].into_iter().flatten().min() else {
+            return projects;
+        };
+
+        if projects.len() <= top {
+            return projects;
+        }
+
+        let mut by_size: Vec<usize> = (0..projects.len()).collect();
+        by_size.sort_by_key(|&i| std::cmp::Reverse(projects[i].total_cleanable_size()));
+        let keep: HashSet<usize> = by_size.into_iter().take(top).collect();
+
+        projects
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| keep.contains(i))
+            .map(|(_, p)| p)
+            .collect()
     }
 
-    fn find_rust_projects(
+    /// re-analyze a single project directory without walking the whole tree
+    pub async fn rescan_project(&self, project_dir: &Path) -> Result<RustProject> {
+        let cli_clone = self.cli.clone();
+        let project_dir = project_dir.to_path_buf();
+        task::spawn_blocking(move || {
+            let last_modified = Self::get_last_modified_time(&project_dir)?;
+            let cargo_cache = Self::measure_cargo_cache(&cli_clone)?;
+            let mut project = Self::analyze_rust_project(
+                &project_dir,
+                last_modified,
+                &cli_clone,
+                &cargo_cache,
+                &mut ScanProfile::default(),
+            )?;
+            project.protected = IgnoreRules::new()
+                .is_protected(&project_dir, &cli_clone.get_search_directory());
+            Ok(project)
+        }).await?
+    }
+
+    /// walk the search directory and analyze every project found, the blocking core of
+    /// the scan methods (public for the benchmarks)
+    pub fn find_rust_projects(
         search_dir: &Path, 
         excluded_dirs: &[String], 
-        cli: &Cli
+        cli: &Cli,
+        stats: &mut ScanStats,
+        sender: Option<&UnboundedSender<RustProject>>,
     ) -> Result<Vec<RustProject>> {
         let mut projects = Vec::new();
-        let mut processed_paths = std::collections::HashSet::new();
+        let mut largest: BinaryHeap<std::cmp::Reverse<BySize>> = BinaryHeap::new();
+        let mut processed_paths = HashSet::new();
+        let mut ignore_rules = IgnoreRules::new();
+        let mut subtree_times: HashMap<PathBuf, Duration> = HashMap::new();
+        let mut entry_counts: HashMap<PathBuf, u64> = HashMap::new();
+        let mut project_dirs = Vec::new();
+        let mut current_subtree: Option<PathBuf> = None;
+        let mut last_entry_at = Instant::now();
+        let walk_started = Instant::now();
+        let deadline = cli.timeout.map(|timeout| walk_started + timeout);
+        let own_dirs = Self::own_dirs_below(search_dir, &utils::rskill_dirs());
+        // the cargo cache is the same for every project, walk it once
+        let cargo_cache = Self::measure_cargo_cache(cli)?;
 
         for entry in WalkDir::new(search_dir)
-            .follow_links(false)
+            .follow_links(cli.follow_links)
             .max_depth(if cli.full { 10 } else { 5 })
             .into_iter()
+            // Prune excluded directories so we never descend into them
+            .filter_entry(|e| !Self::is_excluded_path(e.path(), search_dir, excluded_dirs, &own_dirs, cli.exclude_hidden))
             .filter_map(|e| e.ok())
         {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                stats.timed_out = true;
+                break;
+            }
+
             let path = entry.path();
             
-            // Skip if this is an excluded directory
-            if Self::is_excluded_path(path, excluded_dirs, cli.exclude_hidden) {
+            // Attribute the time since the previous entry to the subtree it was in
+            let now = Instant::now();
+            if let Some(subtree) = current_subtree.take() {
+                *subtree_times.entry(subtree).or_default() += now - last_entry_at;
+            }
+            last_entry_at = now;
+            current_subtree = path
+                .strip_prefix(search_dir)
+                .ok()
+                .and_then(|p| p.components().next())
+                .map(|c| search_dir.join(c));
+            
+            if entry.file_type().is_dir() {
+                stats.directories_scanned += 1;
+            }
+            if let Some(parent) = path.parent().filter(|_| entry.depth() > 0) {
+                stats.entries_walked += 1;
+                match entry_counts.get_mut(parent) {
+                    Some(count) => *count += 1,
+                    None => {
+                        entry_counts.insert(parent.to_path_buf(), 1);
+                    }
+                }
+            }
+
+            // an unreadable archive is just not reported, like an unreadable directory
+            if cli.peek_archives && entry.file_type().is_file() && archive::is_archive(path) {
+                if let Ok(Some(summary)) = archive::peek(path, deadline) {
+                    stats.archives.push(summary);
+                }
                 continue;
             }
 
             // Look for Cargo.toml files
             if path.file_name() == Some(std::ffi::OsStr::new("Cargo.toml")) {
                 let project_dir = path.parent().unwrap();
+                project_dirs.push(project_dir.to_path_buf());
+                
+                // Avoid processing the same project multiple times, even when it's
+                // reachable through a symlink or bind mount as well as its real path
+                let canonical_dir = fs::canonicalize(project_dir)
+                    .unwrap_or_else(|_| project_dir.to_path_buf());
+                if !processed_paths.insert(canonical_dir) {
+                    continue;
+                }
                 
-                // Avoid processing the same project multiple times
-                if processed_paths.contains(project_dir) {
+                if cli.exclude_workspace_members && Self::is_workspace_member(project_dir, search_dir) {
                     continue;
                 }
                 
-                processed_paths.insert(project_dir.to_path_buf());
+                if cli.exclude_cwd && utils::contains_current_dir(project_dir) {
+                    continue;
+                }
+                
+                // Apply the cheap mtime filters before sizing the target directory
+                let Ok(last_modified) = Self::get_last_modified_time(project_dir) else {
+                    continue;
+                };
+                let days_since_modified = last_modified.map(|dt| (Utc::now() - dt).num_days());
+                if !cli.matches_age_filters(days_since_modified) {
+                    continue;
+                }
                 
-                if let Ok(project) = Self::analyze_rust_project(project_dir, cli) {
-                    projects.push(project);
+                let analyzed = Self::analyze_rust_project(project_dir, last_modified, cli, &cargo_cache, &mut stats.profile);
+                if let Err(RskillError::InvalidManifest { path, reason }) = analyzed {
+                    stats.skipped_manifests.push((path, reason));
+                } else if let Ok(mut project) = analyzed {
+                    project.protected = ignore_rules.is_protected(project_dir, search_dir);
+                    if let Some(sender) = sender {
+                        // streamed projects belong to the receiver, nothing is kept here;
+                        // the receiver going away just means nobody is watching anymore
+                        stats.projects_found += 1;
+                        let _ = sender.send(project);
+                        continue;
+                    }
+                    match cli.max_results {
+                        Some(max) => {
+                            largest.push(std::cmp::Reverse(BySize(project)));
+                            if largest.len() > max {
+                                largest.pop();
+                            }
+                        }
+                        None => projects.push(project),
+                    }
                 }
             }
         }
 
-        // Sort projects according to CLI preferences
-        Self::sort_projects(&mut projects, &cli.sort, cli.gb);
+        if let Some(subtree) = current_subtree {
+            *subtree_times.entry(subtree).or_default() += last_entry_at.elapsed();
+        }
+        stats.slowest_subtree = subtree_times.into_iter().max_by_key(|(_, time)| *time);
+        stats.heaviest_subtree = Self::heaviest_subtree(search_dir, entry_counts, &project_dirs, Path::new(&cli.target));
+        stats.profile.walk = walk_started
+            .elapsed()
+            .saturating_sub(stats.profile.parse + stats.profile.sizing);
+
+        let sort_started = Instant::now();
+        projects.extend(largest.into_iter().map(|std::cmp::Reverse(BySize(project))| project));
+        
+        Self::sort_for_cli(&mut projects, cli);
+        stats.profile.sort = sort_started.elapsed();
         
         Ok(projects)
     }
 
-    fn is_excluded_path(path: &Path, excluded_dirs: &[String], exclude_hidden: bool) -> bool {
+    /// roll the per-directory entry counts up to every ancestor, then pick the directory
+    /// with the most entries that neither holds a project nor is inside a target
+    fn heaviest_subtree(
+        search_dir: &Path,
+        mut entry_counts: HashMap<PathBuf, u64>,
+        project_dirs: &[PathBuf],
+        target: &Path,
+    ) -> Option<(PathBuf, u64)> {
+        let mut dirs: Vec<PathBuf> = entry_counts.keys().cloned().collect();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in &dirs {
+            let Some(parent) = dir.parent().filter(|_| dir.as_path() != search_dir) else {
+                continue;
+            };
+            let count = entry_counts[dir];
+            *entry_counts.entry(parent.to_path_buf()).or_default() += count;
+        }
+
+        // every directory holding a project, and every target, looked up per directory
+        // rather than comparing each directory with each project
+        let mut holding_projects: HashSet<&Path> = HashSet::new();
+        for project in project_dirs {
+            for ancestor in project.ancestors() {
+                if !holding_projects.insert(ancestor) {
+                    break;
+                }
+            }
+        }
+        let targets: HashSet<PathBuf> = project_dirs.iter().map(|project| project.join(target)).collect();
+
+        let (mut heaviest, mut count) = entry_counts
+            .iter()
+            .filter(|(dir, _)| dir.starts_with(search_dir) && dir.as_path() != search_dir)
+            .filter(|(dir, _)| {
+                !holding_projects.contains(dir.as_path()) && !dir.ancestors().any(|a| targets.contains(a))
+            })
+            .max_by_key(|(_, count)| **count)
+            .map(|(dir, count)| (dir.clone(), *count))?;
+
+        // name the directory that really holds the entries, `node_modules` rather than
+        // the `frontend` around it
+        while let Some((child, child_count)) = entry_counts
+            .iter()
+            .filter(|(dir, _)| dir.parent() == Some(heaviest.as_path()))
+            .max_by_key(|(_, count)| **count)
+            .filter(|(_, child_count)| **child_count * 10 >= count * 9)
+        {
+            (heaviest, count) = (child.clone(), *child_count);
+        }
+        Some((heaviest, count))
+    }
+
+    fn sort_for_cli(projects: &mut [RustProject], cli: &Cli) {
+        // Sort projects according to CLI preferences, sizes are unknown with --no-sizes
+        let sort_by = match cli.sort {
+            SortBy::Size if cli.no_sizes => &SortBy::Path,
+            ref sort_by => sort_by,
+        };
+        Self::sort_projects(projects, sort_by, cli.then_by.as_ref(), cli.reverse);
+    }
+
+    /// rskill's own directories as the walk below `search_dir` spells them, so they can be
+    /// skipped with a plain prefix check
+    fn own_dirs_below(search_dir: &Path, own_dirs: &[PathBuf]) -> Vec<PathBuf> {
+        let Ok(absolute_search) = std::path::absolute(search_dir) else {
+            return Vec::new();
+        };
+        own_dirs
+            .iter()
+            .filter_map(|own| own.strip_prefix(&absolute_search).ok())
+            .map(|relative| search_dir.join(relative))
+            .collect()
+    }
+
+    /// whether a path below the search directory is excluded, the search directory's own
+    /// components (e.g. `.` or a hidden parent) never are; rskill's own directories always are
+    fn is_excluded_path(
+        path: &Path,
+        search_dir: &Path,
+        excluded_dirs: &[String],
+        own_dirs: &[PathBuf],
+        exclude_hidden: bool,
+    ) -> bool {
+        if own_dirs.iter().any(|own| path.starts_with(own)) {
+            return true;
+        }
+        let relative = path.strip_prefix(search_dir).unwrap_or(path);
+
         // Check if any component is in excluded list
-        for component in path.components() {
-            let comp_str = component.as_os_str().to_string_lossy();
+        for component in relative.components() {
+            let std::path::Component::Normal(name) = component else {
+                continue;
+            };
+            let comp_str = name.to_string_lossy();
             
             if excluded_dirs.iter().any(|excluded| comp_str.contains(excluded)) {
                 return true;
@@ -100,13 +494,41 @@ impl ProjectScanner {
         false
     }
 
-    fn analyze_rust_project(project_dir: &Path, cli: &Cli) -> Result<RustProject> {
+    fn analyze_rust_project(
+        project_dir: &Path,
+        last_modified: Option<DateTime<Utc>>,
+        cli: &Cli,
+        cargo_cache: &[BuildArtifact],
+        profile: &mut ScanProfile,
+    ) -> Result<RustProject> {
         let cargo_toml_path = project_dir.join("Cargo.toml");
         let cargo_lock_path = project_dir.join("Cargo.lock");
         
         // Parse Cargo.toml to get project name and info
+        let parse_started = Instant::now();
         let cargo_toml_content = fs::read_to_string(&cargo_toml_path)?;
-        let project_name = Self::extract_project_name(&cargo_toml_content)
+        let parsed = cargo_toml_content.parse::<toml::Table>();
+        let workspace_root = parsed.as_ref().is_ok_and(Self::is_workspace_root);
+        let virtual_manifest = workspace_root && !parsed.as_ref().is_ok_and(Self::has_package_section);
+        if cli.verify_cargo_toml {
+            Self::verify_manifest(&parsed)
+                .map_err(|reason| RskillError::InvalidManifest { path: cargo_toml_path.clone(), reason })?;
+        }
+        let manifest = parsed.ok();
+        let package_field = |field: &str| {
+            manifest
+                .as_ref()
+                .and_then(|manifest| Self::resolve_package_field(manifest, field, project_dir))
+        };
+        let version = package_field("version");
+        let crate_kind = Self::detect_crate_kind(manifest.as_ref(), project_dir, virtual_manifest);
+        let project_name = (!virtual_manifest)
+            .then(|| match manifest {
+                Some(_) => package_field("name"),
+                // fall back to a line scan for manifests the parser rejects
+                None => Self::extract_project_name(&cargo_toml_content),
+            })
+            .flatten()
             .unwrap_or_else(|| {
                 project_dir
                     .file_name()
@@ -115,48 +537,146 @@ impl ProjectScanner {
                     .to_string()
             });
 
+        profile.parse += parse_started.elapsed();
+
         // Check for target directory
-        let target_dir = project_dir.join(&cli.target);
-        let (target_size, target_exists) = if target_dir.exists() {
-            (utils::calculate_dir_size(&target_dir)?, true)
+        let sizing_started = Instant::now();
+        let target_dir = Self::target_dir(project_dir, cli);
+        let target_exists = target_dir.exists();
+        let (target_size, size_capped, largest_file) = if target_exists && !cli.no_sizes && cli.largest_file {
+            utils::calculate_dir_size_with_largest(&target_dir, cli.disk_usage, cli.size_cap)?
+        } else if target_exists && !cli.no_sizes {
+            let (size, capped) = utils::calculate_dir_size_capped(&target_dir, cli.disk_usage, cli.size_cap)?;
+            (size, capped, None)
         } else {
-            (0, false)
+            (0, false, None)
         };
 
-        // Get last modified time
-        let last_modified = Self::get_last_modified_time(project_dir)?;
-
         // Analyze build artifacts
-        let build_artifacts = if target_exists {
-            Self::analyze_build_artifacts(&target_dir)?
+        let mut build_artifacts = if target_exists && !cli.no_sizes {
+            Self::analyze_build_artifacts(&target_dir, cli.disk_usage, cli.size_cap)?
         } else {
             Vec::new()
         };
 
-        // Calculate cargo cache size if requested
-        let cargo_cache_size = if cli.include_cargo_cache {
-            Self::calculate_cargo_cache_size()?
+        let cargo_cache_size = cargo_cache.iter().map(|a| a.size).sum();
+        build_artifacts.extend_from_slice(cargo_cache);
+
+        // Directories from the config's artifact patterns, anything inside the target
+        // directory is listed but already covered by its size
+        let mut custom_artifacts = Self::analyze_custom_artifacts(project_dir, cli)?;
+        if let Some(vendored) = Self::analyze_vendored_deps(project_dir, cli)? {
+            if !custom_artifacts.iter().any(|a| a.path == vendored.path) {
+                custom_artifacts.push(vendored);
+            }
+        }
+        let extra_artifacts: Vec<_> = custom_artifacts
+            .iter()
+            .filter(|a| !(target_exists && a.path.starts_with(&target_dir)))
+            .collect();
+        let extra_artifacts_size = extra_artifacts.iter().map(|a| a.size).sum();
+        let extra_artifacts = extra_artifacts.into_iter().map(|a| a.path.clone()).collect();
+        build_artifacts.extend(custom_artifacts);
+        let sizing_time = sizing_started.elapsed();
+        profile.sizing += sizing_time;
+        profile.project_sizing.push((project_dir.to_path_buf(), sizing_time));
+
+        let parse_started = Instant::now();
+        // Count dependencies, a virtual manifest has no crate of its own to depend on anything
+        let (dependencies_count, workspace_dependencies_count) = if virtual_manifest {
+            (0, 0)
         } else {
-            0
+            Self::count_dependencies(&cargo_toml_content)
+        };
+
+        let workspace_members = match &manifest {
+            Some(manifest) if workspace_root => Self::count_workspace_members(project_dir, manifest),
+            _ => 0,
         };
+        profile.parse += parse_started.elapsed();
 
-        // Count dependencies
-        let dependencies_count = Self::count_dependencies(&cargo_toml_content);
+        let target_device = if target_exists { utils::device_id(&target_dir) } else { None };
+        let last_build = if target_exists { Self::last_build_time(&target_dir) } else { None };
+        let target_symlink = fs::symlink_metadata(&target_dir).is_ok_and(|m| m.file_type().is_symlink());
+        // a configured target outside the project is where other projects build too
+        let shared_target = target_exists && !target_dir.starts_with(utils::normalize_path(project_dir));
 
         Ok(RustProject {
             path: project_dir.to_path_buf(),
             name: project_name,
+            version,
+            crate_kind,
             target_dir: if target_exists { Some(target_dir) } else { None },
+            target_symlink,
+            shared_target,
             target_size,
+            size_known: !cli.no_sizes,
+            size_capped,
+            largest_file,
             last_modified,
-            workspace_root: Self::is_workspace_root(&cargo_toml_content),
+            last_build,
+            workspace_root,
+            virtual_manifest,
+            workspace_members,
             has_lock_file: cargo_lock_path.exists(),
             dependencies_count,
+            workspace_dependencies_count,
             build_artifacts,
             cargo_cache_size,
+            extra_artifacts,
+            extra_artifacts_size,
+            protected: false,
+            git_repo: utils::is_git_repo(project_dir),
+            git_repository: utils::git_repository(project_dir),
+            device: utils::device_id(project_dir),
+            target_device,
         })
     }
 
+    /// binary and library targets from `[[bin]]`/`[lib]` or cargo's default layout
+    /// (`src/main.rs`, `src/bin/`, `src/lib.rs`)
+    fn detect_crate_kind(manifest: Option<&toml::Table>, project_dir: &Path, virtual_manifest: bool) -> Option<CrateKind> {
+        if virtual_manifest {
+            return Some(CrateKind::Workspace);
+        }
+
+        let has_bin = manifest
+            .and_then(|m| m.get("bin"))
+            .and_then(|bin| bin.as_array())
+            .is_some_and(|bins| !bins.is_empty())
+            || project_dir.join("src/main.rs").is_file()
+            || project_dir.join("src/bin").is_dir();
+        let has_lib = manifest.is_some_and(|m| m.contains_key("lib")) || project_dir.join("src/lib.rs").is_file();
+
+        match (has_bin, has_lib) {
+            (true, true) => Some(CrateKind::Both),
+            (true, false) => Some(CrateKind::Bin),
+            (false, true) => Some(CrateKind::Lib),
+            (false, false) => None,
+        }
+    }
+
+    /// a `[package]` string field, following `field.workspace = true` to the
+    /// `[workspace.package]` table of the enclosing workspace root
+    fn resolve_package_field(manifest: &toml::Table, field: &str, project_dir: &Path) -> Option<String> {
+        match manifest.get("package")?.get(field)? {
+            toml::Value::String(value) => Some(value.clone()),
+            toml::Value::Table(table) if table.get("workspace") == Some(&toml::Value::Boolean(true)) => {
+                let project_dir = std::path::absolute(project_dir).ok()?;
+                project_dir.ancestors().skip(1).find_map(|ancestor| {
+                    let content = fs::read_to_string(ancestor.join("Cargo.toml")).ok()?;
+                    let root = content.parse::<toml::Table>().ok()?;
+                    root.get("workspace")?
+                        .get("package")?
+                        .get(field)?
+                        .as_str()
+                        .map(str::to_string)
+                })
+            }
+            _ => None,
+        }
+    }
+
     fn extract_project_name(cargo_toml: &str) -> Option<String> {
         for line in cargo_toml.lines() {
             if line.trim().starts_with("name") {
@@ -174,26 +694,94 @@ impl ProjectScanner {
         None
     }
 
+    /// newest modification time among the manifest, lock file and source files, the
+    /// source directories are walked so `src/bin/` counts too, as is the directory of a
+    /// custom `[lib]` or `[[bin]]` path
     fn get_last_modified_time(project_dir: &Path) -> Result<Option<DateTime<Utc>>> {
         let mut latest = None;
+        let mut consider = |path: &Path| {
+            if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+                let datetime: DateTime<Utc> = modified.into();
+                latest = Some(latest.map_or(datetime, |prev: DateTime<Utc>| prev.max(datetime)));
+            }
+        };
         
-        // check various files for modification time
-        let files_to_check = ["Cargo.toml", "Cargo.lock", "src/main.rs", "src/lib.rs"];
+        for file in ["Cargo.toml", "Cargo.lock", "build.rs"] {
+            consider(&project_dir.join(file));
+        }
         
-        for file in &files_to_check {
-            let file_path = project_dir.join(file);
-            if let Ok(metadata) = fs::metadata(&file_path) {
-                if let Ok(modified) = metadata.modified() {
-                    let datetime: DateTime<Utc> = modified.into();
-                    latest = Some(latest.map_or(datetime, |prev: DateTime<Utc>| prev.max(datetime)));
+        let mut source_dirs: Vec<PathBuf> =
+            ["src", "examples", "benches", "tests"].iter().map(|dir| project_dir.join(dir)).collect();
+        for path in Self::custom_target_paths(project_dir) {
+            let path = project_dir.join(path);
+            consider(&path);
+            if let Some(dir) = path.parent().filter(|dir| !source_dirs.iter().any(|known| dir.starts_with(known))) {
+                // the project root itself would walk everything, the file alone is enough
+                if dir != project_dir {
+                    source_dirs.push(dir.to_path_buf());
                 }
             }
         }
+
+        // capped in depth, deeply nested sources are rare and walking them is slow
+        for dir in &source_dirs {
+            for entry in WalkDir::new(dir)
+                .max_depth(SOURCE_MTIME_DEPTH)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                consider(entry.path());
+            }
+        }
         
         Ok(latest)
     }
 
-    fn analyze_build_artifacts(target_dir: &Path) -> Result<Vec<BuildArtifact>> {
+    /// the `path` of the manifest's `[lib]` and `[[bin]]` targets, relative to the project
+    fn custom_target_paths(project_dir: &Path) -> Vec<PathBuf> {
+        let Some(manifest) = fs::read_to_string(project_dir.join("Cargo.toml"))
+            .ok()
+            .and_then(|content| content.parse::<toml::Table>().ok())
+        else {
+            return Vec::new();
+        };
+        let lib = manifest.get("lib").into_iter();
+        let bins = manifest.get("bin").and_then(|bin| bin.as_array()).into_iter().flatten();
+        lib.chain(bins)
+            .filter_map(|target| target.get("path")?.as_str())
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// the newest entry of any profile's `.fingerprint` directory, cargo rewrites the
+    /// fingerprints of every unit it builds
+    fn last_build_time(target_dir: &Path) -> Option<DateTime<Utc>> {
+        let children = |dir: &Path| {
+            fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .collect::<Vec<_>>()
+        };
+
+        // `<profile>/.fingerprint` or `<triple>/<profile>/.fingerprint`
+        children(target_dir)
+            .into_iter()
+            .flat_map(|dir| {
+                let nested = children(&dir);
+                std::iter::once(dir).chain(nested)
+            })
+            .map(|dir| dir.join(".fingerprint"))
+            .filter(|fingerprints| fingerprints.is_dir())
+            .flat_map(|fingerprints| WalkDir::new(fingerprints).max_depth(2).into_iter().filter_map(|e| e.ok()))
+            .filter_map(|e| e.metadata().ok()?.modified().ok())
+            .map(DateTime::<Utc>::from)
+            .max()
+    }
+
+    fn analyze_build_artifacts(target_dir: &Path, disk_usage: bool, size_cap: Option<u64>) -> Result<Vec<BuildArtifact>> {
         let mut artifacts = Vec::new();
         
         if !target_dir.exists() {
@@ -204,16 +792,15 @@ impl ProjectScanner {
             let path = entry.path();
             
             if path.is_dir() {
-                let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
-                let artifact_type = match dir_name.as_ref() {
-                    "debug" | "release" => ArtifactType::Target,
-                    "incremental" => ArtifactType::IncrementalCompilation,
-                    "deps" => ArtifactType::Dependencies,
-                    "examples" => ArtifactType::Examples,
-                    _ => continue,
+                let Some(artifact_type) = path
+                    .strip_prefix(target_dir)
+                    .ok()
+                    .and_then(|relative| Self::classify_target_path(target_dir, relative))
+                else {
+                    continue;
                 };
                 
-                let size = utils::calculate_dir_size(path).unwrap_or(0);
+                let size = utils::calculate_dir_size_capped(path, disk_usage, size_cap).map_or(0, |(size, _)| size);
                 let last_modified = fs::metadata(path)
                     .and_then(|m| m.modified())
                     .map(DateTime::<Utc>::from)
@@ -224,6 +811,7 @@ impl ProjectScanner {
                     artifact_type,
                     size,
                     last_modified,
+                    label: None,
                 });
             }
         }
@@ -231,139 +819,1959 @@ impl ProjectScanner {
         Ok(artifacts)
     }
 
-    fn calculate_cargo_cache_size() -> Result<u64> {
-        let mut total_size = 0u64;
-        
-        if let Some(home) = dirs::home_dir() {
-            let cargo_dir = home.join(".cargo");
-            
-            // Registry cache
-            let registry_dir = cargo_dir.join("registry");
-            if registry_dir.exists() {
-                total_size += utils::calculate_dir_size(&registry_dir)?;
-            }
-            
-            // Git cache
-            let git_dir = cargo_dir.join("git");
-            if git_dir.exists() {
-                total_size += utils::calculate_dir_size(&git_dir)?;
-            }
+    /// installed rustup toolchains one by one, plus the download cache
+    pub fn rustup_artifacts(disk_usage: bool) -> Result<Vec<BuildArtifact>> {
+        let mut artifacts = Vec::new();
+        let Some(rustup_home) = utils::rustup_home() else {
+            return Ok(artifacts);
+        };
+        let default_toolchain = utils::default_rustup_toolchain();
+
+        let mut toolchains: Vec<_> = fs::read_dir(rustup_home.join("toolchains"))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.path())
+            .collect();
+        toolchains.sort();
+
+        for path in toolchains {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let label = if default_toolchain.as_deref() == Some(name.as_str()) {
+                format!("{} (default)", name)
+            } else {
+                name
+            };
+            artifacts.push(Self::global_artifact(path, ArtifactType::RustupToolchain, label, disk_usage)?);
         }
-        
-        Ok(total_size)
+
+        let downloads = rustup_home.join("downloads");
+        if downloads.exists() {
+            artifacts.push(Self::global_artifact(
+                downloads,
+                ArtifactType::RustupDownloads,
+                "rustup downloads".to_string(),
+                disk_usage,
+            )?);
+        }
+
+        Ok(artifacts)
     }
 
-    fn count_dependencies(cargo_toml: &str) -> usize {
-        let mut in_dependencies = false;
-        let mut count = 0;
-        
-        for line in cargo_toml.lines() {
-            let trimmed = line.trim();
-            
-            if trimmed.starts_with('[') {
-                in_dependencies = trimmed.starts_with("[dependencies")
-                    || trimmed.starts_with("[dev-dependencies")
-                    || trimmed.starts_with("[build-dependencies");
-                continue;
-            }
-            
-            if in_dependencies && !trimmed.is_empty() && !trimmed.starts_with('#') {
-                count += 1;
+    /// the toolchain channel a project pins in `rust-toolchain.toml` or `rust-toolchain`,
+    /// the legacy file may hold the bare channel name instead of TOML
+    pub fn pinned_toolchain(project_dir: &Path) -> Option<String> {
+        ["rust-toolchain.toml", "rust-toolchain"].iter().find_map(|name| {
+            let content = fs::read_to_string(project_dir.join(name)).ok()?;
+            match content.parse::<toml::Table>() {
+                Ok(file) => file.get("toolchain")?.get("channel")?.as_str().map(str::to_string),
+                Err(_) => content.lines().map(str::trim).find(|line| !line.is_empty()).map(str::to_string),
             }
+        })
+    }
+
+    fn global_artifact(
+        path: PathBuf,
+        artifact_type: ArtifactType,
+        label: String,
+        disk_usage: bool,
+    ) -> Result<BuildArtifact> {
+        Ok(BuildArtifact {
+            size: utils::calculate_dir_size(&path, disk_usage)?,
+            last_modified: fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(DateTime::<Utc>::from)
+                .ok(),
+            path,
+            artifact_type,
+            label: Some(label),
+        })
+    }
+
+    /// `<dir>/.cargo/config.toml`, or the older extensionless `config`
+    fn cargo_config(dir: &Path) -> Option<toml::Table> {
+        ["config.toml", "config"]
+            .iter()
+            .find_map(|name| fs::read_to_string(dir.join(".cargo").join(name)).ok())
+            .and_then(|content| content.parse::<toml::Table>().ok())
+    }
+
+    /// the project's target directory, an explicit `--target` name wins over the cargo config
+    fn target_dir(project_dir: &Path, cli: &Cli) -> PathBuf {
+        match Self::configured_target_dir(project_dir) {
+            Some(configured) if cli.target == DEFAULT_TARGET_NAME => configured,
+            _ => project_dir.join(&cli.target),
         }
-        
-        count
     }
 
-    fn is_workspace_root(cargo_toml: &str) -> bool {
-        cargo_toml.contains("[workspace]")
+    /// the directories holding a project's build output: its target, the vendored sources
+    /// and the config's artifact directories, found without sizing anything
+    pub fn artifact_dirs(project_dir: &Path, cli: &Cli) -> Result<Vec<PathBuf>> {
+        let mut cli = cli.clone();
+        cli.no_sizes = true;
+        let mut dirs = vec![Self::target_dir(project_dir, &cli)];
+        dirs.extend(Self::analyze_vendored_deps(project_dir, &cli)?.map(|a| a.path));
+        dirs.extend(Self::analyze_custom_artifacts(project_dir, &cli)?.into_iter().map(|a| a.path));
+        Ok(dirs)
+    }
+
+    /// the target directory cargo would use instead of `<project>/target`, in cargo's order:
+    /// `CARGO_TARGET_DIR`, then `build.target-dir` of the nearest cargo config at or above
+    /// the project, then the one in `$CARGO_HOME/config.toml`; a relative value is resolved
+    /// against the directory holding `.cargo` (the cwd for the variable)
+    fn configured_target_dir(project_dir: &Path) -> Option<PathBuf> {
+        if let Some(dir) = std::env::var_os("CARGO_TARGET_DIR").filter(|value| !value.is_empty()) {
+            return Some(utils::normalize_path(Path::new(&dir)));
+        }
+
+        let target_dir = |config: toml::Table| config.get("build")?.get("target-dir")?.as_str().map(str::to_string);
+        let project_dir = std::path::absolute(project_dir).ok()?;
+        let configured = project_dir
+            .ancestors()
+            .find_map(|dir| Some(Self::resolve_config_path(dir, &target_dir(Self::cargo_config(dir)?)?)))
+            .or_else(|| {
+                // the cargo home is only read here when it isn't an ancestor's `.cargo`
+                let cargo_home = utils::cargo_home()?;
+                let config = ["config.toml", "config"]
+                    .iter()
+                    .find_map(|name| fs::read_to_string(cargo_home.join(name)).ok())?
+                    .parse::<toml::Table>()
+                    .ok()?;
+                Some(Self::resolve_config_path(cargo_home.parent()?, &target_dir(config)?))
+            })?;
+        Some(utils::normalize_path(&configured))
     }
 
-    fn sort_projects(projects: &mut Vec<RustProject>, sort_by: &SortBy, _use_gb: bool) {
-        match sort_by {
-            SortBy::Size => {
-                projects.sort_by(|a, b| b.total_cleanable_size().cmp(&a.total_cleanable_size()));
+    /// a path from a cargo config found in `config_root/.cargo`, with `~/` taken as home
+    fn resolve_config_path(config_root: &Path, value: &str) -> PathBuf {
+        if let Some(home_relative) = value.strip_prefix("~/") {
+            if let Some(home) = dirs::home_dir() {
+                return home.join(home_relative);
             }
-            SortBy::Path => {
-                projects.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        // joining an absolute path replaces the root
+        config_root.join(value)
+    }
+
+    /// the directory `cargo vendor` filled, when the project's `.cargo/config.toml` replaces
+    /// crates-io with it; `[source.<name>] directory` names it, `vendor` by default
+    fn analyze_vendored_deps(project_dir: &Path, cli: &Cli) -> Result<Option<BuildArtifact>> {
+        let config = Self::cargo_config(project_dir);
+        let Some(sources) = config.as_ref().and_then(|c| c.get("source")).and_then(|s| s.as_table()) else {
+            return Ok(None);
+        };
+        let Some(replacement) = sources
+            .get("crates-io")
+            .and_then(|s| s.get("replace-with"))
+            .and_then(|r| r.as_str())
+        else {
+            return Ok(None);
+        };
+        let directory = sources
+            .get(replacement)
+            .and_then(|s| s.get("directory"))
+            .and_then(|d| d.as_str())
+            .unwrap_or("vendor");
+
+        // only a directory inside the project is the project's to clean
+        let inside = Path::new(directory)
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir));
+        let path = project_dir.join(directory);
+        if !inside || !path.is_dir() {
+            return Ok(None);
+        }
+        Ok(Some(BuildArtifact {
+            size: if cli.no_sizes { 0 } else { utils::calculate_dir_size(&path, cli.disk_usage)? },
+            last_modified: fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(DateTime::<Utc>::from)
+                .ok(),
+            path,
+            artifact_type: ArtifactType::VendoredDeps,
+            label: Some("vendored dependencies (cargo vendor)".to_string()),
+        }))
+    }
+
+    /// find the directories matching the config's artifact patterns, each pattern is
+    /// matched one path component at a time relative to the project directory
+    fn analyze_custom_artifacts(project_dir: &Path, cli: &Cli) -> Result<Vec<BuildArtifact>> {
+        let mut artifacts = Vec::new();
+
+        for artifact_pattern in &cli.config.artifacts {
+            let components: Vec<&str> = artifact_pattern
+                .pattern
+                .split('/')
+                .filter(|c| !c.is_empty())
+                .collect();
+            if components.is_empty() {
+                continue;
             }
-            SortBy::LastMod => {
-                projects.sort_by(|a, b| {
-                    match (a.last_modified, b.last_modified) {
-                        (Some(a_time), Some(b_time)) => b_time.cmp(&a_time),
-                        (Some(_), None) => std::cmp::Ordering::Less,
-                        (None, Some(_)) => std::cmp::Ordering::Greater,
-                        (None, None) => std::cmp::Ordering::Equal,
-                    }
+
+            let matches = WalkDir::new(project_dir)
+                .min_depth(components.len())
+                .max_depth(components.len())
+                .into_iter()
+                .filter_entry(|e| {
+                    let Ok(relative) = e.path().strip_prefix(project_dir) else {
+                        return false;
+                    };
+                    relative
+                        .components()
+                        .zip(&components)
+                        .all(|(c, pattern)| utils::matches_wildcard(pattern, &c.as_os_str().to_string_lossy()))
+                })
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_dir());
+
+            for entry in matches {
+                let path = entry.path();
+                artifacts.push(BuildArtifact {
+                    path: path.to_path_buf(),
+                    artifact_type: artifact_pattern.artifact_type.clone(),
+                    size: if cli.no_sizes { 0 } else { utils::calculate_dir_size(path, cli.disk_usage)? },
+                    last_modified: fs::metadata(path)
+                        .and_then(|m| m.modified())
+                        .map(DateTime::<Utc>::from)
+                        .ok(),
+                    label: Some(artifact_pattern.pattern.clone()),
                 });
             }
         }
+
+        Ok(artifacts)
     }
 
-    pub async fn print_projects(&self, projects: &[RustProject]) -> Result<()> {
-        if projects.is_empty() {
-            print!("No Rust projects found.");
-            return Ok(());
-        }
+    /// classify a directory by its place in the `target/[<triple>/]<profile>/<kind>` layout,
+    /// so unrelated directories that merely share a name aren't counted; besides `debug`
+    /// and `release`, any directory holding a `deps` directory is a (custom) profile
+    fn classify_target_path(target_dir: &Path, relative: &Path) -> Option<ArtifactType> {
+        let components: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+        let is_profile = |dir: &Path| {
+            matches!(dir.file_name().and_then(|name| name.to_str()), Some("debug" | "release"))
+                || target_dir.join(dir).join("deps").is_dir()
+        };
+        let is_triple = |name: &str| name.matches('-').count() >= 2 && !is_profile(Path::new(name));
+        let (triple, layout) = match components.as_slice() {
+            [triple, rest @ ..] if !rest.is_empty() && is_triple(triple) => (Some(triple.as_ref()), rest),
+            all => (None, all),
+        };
+        let profile_dir = |profile: &str| triple.map_or_else(|| PathBuf::from(profile), |t| Path::new(t).join(profile));
 
-        println!(
-            "\n{:<30} {:<15} {:<20} {:<15} {:<10}",
-            "Project Name".bold(),
-            "Size".bold(),
-            "Path".bold(),
-            "Last Modified".bold(),
-            "Status".bold()
-        );
-        println!("{}", "─".repeat(100));
+        match layout {
+            [dir] if dir == "doc" => Some(ArtifactType::Documentation),
+            [profile] if is_profile(&profile_dir(profile)) => Some(ArtifactType::Target),
+            [profile, kind] if is_profile(&profile_dir(profile)) => match kind.as_ref() {
+                "incremental" => Some(ArtifactType::IncrementalCompilation),
+                "deps" => Some(ArtifactType::Dependencies),
+                "examples" => Some(ArtifactType::Examples),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
 
-        for project in projects {
-            let size_str = project.format_size(self.cli.gb);
-            let path_str = project.path.display().to_string();
-            let path_display = if path_str.len() > 18 {
-                format!("...{}", &path_str[path_str.len() - 15..])
-            } else {
-                path_str
-            };
+    /// the cargo cache artifacts when `--include-cargo-cache` asks for them
+    fn measure_cargo_cache(cli: &Cli) -> Result<Vec<BuildArtifact>> {
+        if cli.include_cargo_cache && !cli.no_sizes {
+            Self::analyze_cargo_cache(cli.disk_usage)
+        } else {
+            Ok(Vec::new())
+        }
+    }
 
-            let last_mod = project
-                .last_modified
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-                .unwrap_or_else(|| "Unknown".to_string());
+    /// measure the cargo caches piece by piece, extracted sources and git checkouts are
+    /// regenerated locally while the rest has to be downloaded again
+    fn analyze_cargo_cache(disk_usage: bool) -> Result<Vec<BuildArtifact>> {
+        let mut artifacts = Vec::new();
+        
+        let Some(cargo_dir) = utils::cargo_home() else {
+            return Ok(artifacts);
+        };
+        
+        let parts = [
+            ("registry/index", ArtifactType::CargoRegistry, "registry index"),
+            ("registry/cache", ArtifactType::CargoRegistry, "registry cache (downloaded crates)"),
+            ("registry/src", ArtifactType::CargoRegistry, "registry src (regenerable)"),
+            ("git/db", ArtifactType::CargoGitCache, "git db"),
+            ("git/checkouts", ArtifactType::CargoGitCache, "git checkouts (regenerable)"),
+        ];
+        
+        for (dir, artifact_type, label) in parts {
+            let path = cargo_dir.join(dir);
+            if !path.exists() {
+                continue;
+            }
+            
+            artifacts.push(Self::global_artifact(path, artifact_type, label.to_string(), disk_usage)?);
+        }
+        
+        Ok(artifacts)
+    }
 
-            let status = if project.is_likely_active() {
-                "Active".green()
-            } else {
-                "Stale".yellow()
-            };
+    /// extracted crate sources under `registry/src/<index>/` whose `.crate` archive is still
+    /// in `registry/cache/<index>/`, cargo re-extracts those on demand
+    pub fn redundant_registry_sources(cargo_dir: &Path, disk_usage: bool) -> Result<Vec<BuildArtifact>> {
+        let mut artifacts = Vec::new();
+        let registry = cargo_dir.join("registry");
 
-            let warning = if !project.is_likely_active() && project.total_cleanable_size() == 0 {
-                ""
-            } else if !project.target_dir.is_some() {
-                " (no target)"
-            } else {
-                ""
-            };
+        for index in fs::read_dir(registry.join("src")).into_iter().flatten().filter_map(|e| e.ok()) {
+            let cache = registry.join("cache").join(index.file_name());
+            let mut sources: Vec<_> = fs::read_dir(index.path())
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .collect();
+            sources.sort_by_key(|e| e.file_name());
 
-            println!(
-                "{:<30} {:<15} {:<20} {:<15} {:<10}{}",
-                project.name,
-                size_str.cyan(),
-                path_display,
-                last_mod,
-                status,
-                warning.red()
-            );
+            for source in sources {
+                let name = source.file_name().to_string_lossy().to_string();
+                if !cache.join(format!("{}.crate", name)).is_file() {
+                    continue;
+                }
+                artifacts.push(Self::global_artifact(source.path(), ArtifactType::CargoRegistry, name, disk_usage)?);
+            }
         }
 
-        let total_size: u64 = projects.iter().map(|p| p.total_cleanable_size()).sum();
-        let total_size_str = if self.cli.gb {
-            format!("{:.2} GB", total_size as f64 / (1024.0 * 1024.0 * 1024.0))
-        } else {
-            format!("{:.2} MB", total_size as f64 / (1024.0 * 1024.0))
+        Ok(artifacts)
+    }
+
+    /// count distinct dependencies across the normal, dev and build tables (including
+    /// target-specific ones), returning the total and how many are inherited from the workspace
+    fn count_dependencies(cargo_toml: &str) -> (usize, usize) {
+        const SECTIONS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+        let Ok(manifest) = cargo_toml.parse::<toml::Table>() else {
+            return (0, 0);
         };
 
-        println!("\nTotal cleanable space: {}", total_size_str.bold().green());
-        
-        Ok(())
+        let target_tables = manifest
+            .get("target")
+            .and_then(|t| t.as_table())
+            .into_iter()
+            .flat_map(|t| t.values())
+            .filter_map(|v| v.as_table());
+
+        let mut dependencies: HashMap<&str, bool> = HashMap::new();
+        for table in std::iter::once(&manifest).chain(target_tables) {
+            for section in SECTIONS {
+                let Some(deps) = table.get(section).and_then(|d| d.as_table()) else {
+                    continue;
+                };
+
+                for (name, spec) in deps {
+                    let inherited = spec
+                        .get("workspace")
+                        .and_then(|w| w.as_bool())
+                        .unwrap_or(false);
+                    *dependencies.entry(name.as_str()).or_default() |= inherited;
+                }
+            }
+        }
+
+        let inherited = dependencies.values().filter(|inherited| **inherited).count();
+        (dependencies.len(), inherited)
+    }
+
+    fn is_workspace_root(manifest: &toml::Table) -> bool {
+        manifest.contains_key("workspace")
+    }
+
+    /// why a manifest isn't a crate or workspace, for `--verify-cargo-toml`
+    fn verify_manifest(parsed: &std::result::Result<toml::Table, toml::de::Error>) -> std::result::Result<(), String> {
+        match parsed {
+            Err(err) => Err(format!("not valid TOML: {}", err.message())),
+            Ok(manifest) if !manifest.contains_key("package") && !manifest.contains_key("workspace") => {
+                Err("no [package] or [workspace] table".to_string())
+            }
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// a manifest without `[package]` is a virtual (workspace-only) manifest
+    fn has_package_section(manifest: &toml::Table) -> bool {
+        manifest.contains_key("package")
+    }
+
+    /// count member crates on disk matching the workspace's `members` patterns
+    fn count_workspace_members(workspace_dir: &Path, manifest: &toml::Table) -> usize {
+        let members = Self::extract_workspace_members(manifest);
+        let max_depth = members
+            .iter()
+            .map(|m| m.split('/').filter(|p| !p.is_empty() && *p != ".").count())
+            .max()
+            .unwrap_or(0);
+
+        WalkDir::new(workspace_dir)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_dir() && e.path().join("Cargo.toml").exists())
+            .filter(|e| {
+                let relative = e
+                    .path()
+                    .strip_prefix(workspace_dir)
+                    .unwrap_or(e.path())
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                members
+                    .iter()
+                    .any(|member| Self::matches_member_pattern(member.trim_end_matches('/'), &relative))
+            })
+            .count()
+    }
+
+    /// check whether an ancestor (within the search root) is a workspace listing this crate
+    fn is_workspace_member(project_dir: &Path, search_dir: &Path) -> bool {
+        for ancestor in project_dir.ancestors().skip(1) {
+            if !ancestor.starts_with(search_dir) {
+                break;
+            }
+
+            let Some(manifest) = fs::read_to_string(ancestor.join("Cargo.toml"))
+                .ok()
+                .and_then(|content| content.parse::<toml::Table>().ok())
+            else {
+                continue;
+            };
+
+            if !Self::is_workspace_root(&manifest) {
+                continue;
+            }
+
+            let Ok(relative) = project_dir.strip_prefix(ancestor) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+
+            if Self::extract_workspace_members(&manifest)
+                .iter()
+                .any(|member| Self::matches_member_pattern(member.trim_end_matches('/'), &relative))
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// collect the `members = [...]` entries of a `[workspace]` table
+    fn extract_workspace_members(manifest: &toml::Table) -> Vec<String> {
+        manifest
+            .get("workspace")
+            .and_then(|workspace| workspace.get("members"))
+            .and_then(toml::Value::as_array)
+            .map(|members| members.iter().filter_map(toml::Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// match a workspace member pattern such as `crates/*` against a relative path
+    fn matches_member_pattern(pattern: &str, relative: &str) -> bool {
+        let pattern_parts: Vec<&str> = pattern.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+        let path_parts: Vec<&str> = relative.split('/').filter(|p| !p.is_empty()).collect();
+
+        pattern_parts.len() == path_parts.len()
+            && pattern_parts
+                .iter()
+                .zip(&path_parts)
+                .all(|(pattern, part)| utils::matches_wildcard(pattern, part))
+    }
+
+    /// sort by the primary key, then the secondary one, then path so equal entries
+    /// always come out in the same order
+    fn sort_projects(projects: &mut [RustProject], sort_by: &SortBy, then_by: Option<&SortBy>, reverse: bool) {
+        projects.sort_by(|a, b| {
+            let ordering = Self::compare_by(a, b, sort_by)
+                .then_with(|| then_by.map_or(std::cmp::Ordering::Equal, |key| Self::compare_by(a, b, key)))
+                .then_with(|| a.path.cmp(&b.path));
+            if reverse { ordering.reverse() } else { ordering }
+        });
+    }
+
+    /// natural order for each key: largest, alphabetical and most recent first
+    fn compare_by(a: &RustProject, b: &RustProject, key: &SortBy) -> std::cmp::Ordering {
+        match key {
+            SortBy::Size => b.total_cleanable_size().cmp(&a.total_cleanable_size()),
+            SortBy::Path => a.path.cmp(&b.path),
+            SortBy::LastMod => match (a.last_modified, b.last_modified) {
+                (Some(a_time), Some(b_time)) => b_time.cmp(&a_time),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            },
+        }
+    }
+
+    pub async fn print_projects(&self, projects: &[RustProject]) -> Result<()> {
+        if projects.is_empty() {
+            print!("No Rust projects found.");
+            return Ok(());
+        }
+
+        if let (Some(depth), false) = (self.cli.group_by_parent, self.cli.summary_only) {
+            self.print_group_table(projects, depth);
+        } else if self.cli.bars && !self.cli.summary_only {
+            self.print_bar_list(projects);
+        } else if !self.cli.summary_only {
+            self.print_project_table(projects);
+        }
+
+        if self.cli.include_cargo_cache && !self.cli.summary_only {
+            self.print_cargo_cache_breakdown(&projects[0]);
+        }
+
+        if self.cli.include_rustup && !self.cli.summary_only {
+            self.print_rustup_breakdown()?;
+        }
+
+        if !self.cli.summary_only && !self.cli.no_sizes {
+            self.print_filesystem_breakdown(projects);
+        }
+
+        let shared_targets = Self::shared_targets(projects);
+        if !self.cli.summary_only {
+            self.print_shared_targets(&shared_targets);
+            self.print_worktree_repositories(projects);
+        }
+
+        if !self.cli.summary_only {
+            self.print_age_histogram(projects);
+        }
+
+        // a shared target is listed under each project that owns it but counted once,
+        // workspace members sharing their root's target count it as 0 already
+        let counted_twice: u64 = shared_targets
+            .iter()
+            .map(|(_, sharing)| {
+                let sizes = sharing.iter().map(|p| p.target_size);
+                sizes.clone().sum::<u64>() - sizes.max().unwrap_or(0)
+            })
+            .sum();
+        let total_size: u64 =
+            projects.iter().map(|p| p.total_cleanable_size()).sum::<u64>() - counted_twice + Self::cargo_cache_size(projects);
+        let total_size_str = if self.cli.no_sizes {
+            "unknown".to_string()
+        } else if self.cli.gb {
+            format!("{:.2} GB", total_size as f64 / (1024.0 * 1024.0 * 1024.0))
+        } else {
+            format!("{:.2} MB", total_size as f64 / (1024.0 * 1024.0))
+        };
+
+        if self.cli.summary_only {
+            let active_count = projects.iter().filter(|p| p.is_likely_active(DEFAULT_STALE_DAYS)).count();
+            println!(
+                "Total cleanable space: {} ({} projects, {} active, {} stale)",
+                total_size_str.bold().green(),
+                projects.len(),
+                active_count,
+                projects.len() - active_count
+            );
+        } else {
+            println!("\nTotal cleanable space: {}", total_size_str.bold().green());
+        }
+
+        if self.cli.dedup_report {
+            println!(
+                "Duplicated compiled dependencies: {} (saved by a shared target directory)",
+                utils::format_size(Self::duplicate_deps_size(projects, self.cli.disk_usage), self.cli.gb).bold()
+            );
+        }
+        
+        Ok(())
+    }
+
+    /// project count and cleanable bytes per age bucket, projects without a known
+    /// modification time are left out
+    fn age_histogram(projects: &[RustProject]) -> Vec<(&'static str, usize, u64)> {
+        let mut buckets: Vec<_> = AGE_BUCKETS.iter().map(|(label, _)| (*label, 0, 0)).collect();
+        for project in projects {
+            let Some(days) = project.days_since_modified() else {
+                continue;
+            };
+            let bucket = AGE_BUCKETS.iter().position(|(_, below)| days < *below).unwrap_or(AGE_BUCKETS.len() - 1);
+            buckets[bucket].1 += 1;
+            buckets[bucket].2 += project.total_cleanable_size();
+        }
+        buckets
+    }
+
+    fn print_age_histogram(&self, projects: &[RustProject]) {
+        let buckets = Self::age_histogram(projects);
+        // bars follow the size, or the count when sizes weren't measured
+        let bar_value = |&(_, count, size): &(&str, usize, u64)| if self.cli.no_sizes { count as u64 } else { size };
+        let largest = buckets.iter().map(bar_value).max().unwrap_or(0);
+
+        println!("\n{}", "Projects by age".bold());
+        for bucket in &buckets {
+            let (label, count, size) = *bucket;
+            let size_str = if self.cli.no_sizes { "-".to_string() } else { utils::format_size(size, self.cli.gb) };
+            println!(
+                "  {:<8} {} {:>4} projects {:>12}",
+                label,
+                utils::size_bar(bar_value(bucket), largest, 20).cyan(),
+                count,
+                size_str
+            );
+        }
+    }
+
+    /// target directories that several projects resolve to (e.g. through symlinks), with
+    /// the projects sharing each one
+    fn shared_targets(projects: &[RustProject]) -> Vec<(PathBuf, Vec<&RustProject>)> {
+        let mut by_target: BTreeMap<PathBuf, Vec<&RustProject>> = BTreeMap::new();
+        for project in projects {
+            let target = project.target_dir.as_deref().or_else(|| Self::workspace_target(project, projects));
+            if let Some(target) = target.and_then(|dir| dir.canonicalize().ok()) {
+                by_target.entry(target).or_default().push(project);
+            }
+        }
+
+        by_target.into_iter().filter(|(_, sharing)| sharing.len() > 1).collect()
+    }
+
+    /// the target directory of the closest listed workspace root that has `project` as a
+    /// member, where cargo builds a member that has no target of its own
+    fn workspace_target<'a>(project: &RustProject, projects: &'a [RustProject]) -> Option<&'a Path> {
+        let root = projects
+            .iter()
+            .filter(|root| root.workspace_root && root.path != project.path && project.path.starts_with(&root.path))
+            .max_by_key(|root| root.path.components().count())?;
+
+        let manifest = fs::read_to_string(root.path.join("Cargo.toml")).ok()?.parse::<toml::Table>().ok()?;
+        let relative = project.path.strip_prefix(&root.path).ok()?.to_string_lossy().replace('\\', "/");
+        Self::extract_workspace_members(&manifest)
+            .iter()
+            .any(|member| Self::matches_member_pattern(member.trim_end_matches('/'), &relative))
+            .then_some(root.target_dir.as_deref())
+            .flatten()
+    }
+
+    fn print_shared_targets(&self, shared_targets: &[(PathBuf, Vec<&RustProject>)]) {
+        if shared_targets.is_empty() {
+            return;
+        }
+
+        println!("\n{}", "Shared target directories (counted once in the total)".bold());
+        for (target, sharing) in shared_targets {
+            let size = if self.cli.no_sizes {
+                "unknown".to_string()
+            } else {
+                utils::format_size(sharing.iter().map(|p| p.target_size).max().unwrap_or(0), self.cli.gb)
+            };
+            let names: Vec<_> = sharing.iter().map(|p| p.display_name()).collect();
+            println!(
+                "  {} crates share target at {}, size {}: {}",
+                sharing.len(),
+                utils::display_path(target, &self.cli.paths),
+                size.cyan(),
+                names.join(", ")
+            );
+        }
+    }
+
+    /// repositories whose projects are spread over several worktrees, with their
+    /// worktree count and reclaimable space in total
+    fn worktree_repositories(projects: &[RustProject]) -> Vec<(PathBuf, usize, u64)> {
+        let mut by_repository: BTreeMap<&Path, (HashSet<&Path>, u64)> = BTreeMap::new();
+        for project in projects {
+            let (Some(repository), Some(checkout)) =
+                (project.git_repository.as_deref(), utils::git_checkout_root(&project.path))
+            else {
+                continue;
+            };
+            let (checkouts, size) = by_repository.entry(repository).or_default();
+            checkouts.insert(checkout);
+            *size += project.total_cleanable_size();
+        }
+
+        by_repository
+            .into_iter()
+            .filter(|(_, (checkouts, _))| checkouts.len() > 1)
+            .map(|(repository, (checkouts, size))| (repository.to_path_buf(), checkouts.len(), size))
+            .collect()
+    }
+
+    fn print_worktree_repositories(&self, projects: &[RustProject]) {
+        let repositories = Self::worktree_repositories(projects);
+        if repositories.is_empty() {
+            return;
+        }
+
+        println!("\n{}", "Repositories with several worktrees".bold());
+        for (repository, worktrees, size) in repositories {
+            let size = if self.cli.no_sizes {
+                "unknown".to_string()
+            } else {
+                utils::format_size(size, self.cli.gb)
+            };
+            println!(
+                "  {:<40} {} worktrees, {}",
+                utils::display_path(&repository, &self.cli.paths),
+                worktrees,
+                size.cyan()
+            );
+        }
+    }
+
+    /// bytes of `deps` files found in more than one project, every project's copy past the
+    /// first counts; cargo puts a hash of the crate version, features and compiler into each
+    /// file name, so the same name and size means the same artifact. Copies within one
+    /// project (its debug and release profiles) aren't saved by sharing a target
+    fn duplicate_deps_size(projects: &[RustProject], disk_usage: bool) -> u64 {
+        let mut owners: HashMap<(std::ffi::OsString, u64), HashSet<usize>> = HashMap::new();
+        // a target shared by several projects is read once
+        let mut seen_dirs = HashSet::new();
+        let deps_dirs = projects.iter().enumerate().flat_map(|(index, p)| {
+            p.build_artifacts
+                .iter()
+                .filter(|a| a.artifact_type == ArtifactType::Dependencies)
+                .map(move |a| (index, a))
+        });
+
+        for (index, deps_dir) in deps_dirs {
+            if !seen_dirs.insert(&deps_dir.path) {
+                continue;
+            }
+            for entry in WalkDir::new(&deps_dir.path).max_depth(1).into_iter().filter_map(|e| e.ok()) {
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                if let Ok(metadata) = entry.metadata() {
+                    let size = utils::file_size(&metadata, disk_usage);
+                    owners.entry((entry.file_name().to_owned(), size)).or_default().insert(index);
+                }
+            }
+        }
+
+        owners.into_iter().map(|((_, size), projects)| size * (projects.len() as u64 - 1)).sum()
+    }
+
+    /// report scan timing on stderr so it never mixes with table or JSON output
+    pub fn print_scan_stats(&self, stats: &ScanStats) {
+        eprintln!("{}", stats.summary().dimmed());
+        if !self.cli.hide_errors {
+            for (path, reason) in &stats.skipped_manifests {
+                eprintln!("{} {}", "Skipped".yellow(), utils::escape_control(&format!("{}: {}", path.display(), reason)));
+            }
+        }
+        if !stats.archives.is_empty() {
+            self.print_archives(&stats.archives);
+        }
+        if let Some((subtree, entries)) = &stats.heaviest_subtree {
+            // worth excluding once it's most of the walk, not just the biggest leftover
+            if *entries >= EXCLUDE_TIP_MIN_ENTRIES && *entries * 2 >= stats.entries_walked {
+                let name = subtree.file_name().unwrap_or_default().to_string_lossy();
+                eprintln!(
+                    "{} add --exclude {} to skip {} files next time.",
+                    "Tip:".cyan(),
+                    utils::escape_control(&utils::shell_quote(&name)),
+                    utils::format_count(*entries)
+                );
+            }
+        }
+        if let Some((subtree, time)) = &stats.slowest_subtree {
+            if stats.elapsed.as_secs_f64() >= 1.0 {
+                eprintln!(
+                    "{}",
+                    format!("Slowest subtree: {} ({:.1}s)", subtree.display(), time.as_secs_f64()).dimmed()
+                );
+            }
+        }
+    }
+
+    fn print_archives(&self, archives: &[ArchiveSummary]) {
+        eprintln!("{}", "Archived projects".bold());
+        for summary in archives {
+            let target = utils::format_size(summary.target_size, self.cli.gb);
+            let target = if summary.target_size > 0 { target.yellow() } else { target.dimmed() };
+            eprintln!(
+                "  {} {} of target in {} uncompressed",
+                utils::escape_path(&summary.path),
+                target,
+                utils::format_size(summary.total_size, self.cli.gb)
+            );
+        }
+    }
+
+    fn print_rustup_breakdown(&self) -> Result<()> {
+        let artifacts = Self::rustup_artifacts(self.cli.disk_usage)?;
+        if artifacts.is_empty() {
+            return Ok(());
+        }
+
+        println!("\n{}", "Rustup".bold());
+        for artifact in artifacts {
+            println!(
+                "  {:<40} {}",
+                artifact.label.as_deref().unwrap_or_default(),
+                utils::format_size(artifact.size, self.cli.gb).cyan()
+            );
+        }
+        Ok(())
+    }
+
+    /// only printed when some target lives on another filesystem than its project,
+    /// otherwise everything is reclaimed from the same place
+    fn print_filesystem_breakdown(&self, projects: &[RustProject]) {
+        let by_device = Self::reclaimable_by_filesystem(projects, utils::cargo_home().as_deref());
+        if by_device.len() < 2 {
+            return;
+        }
+
+        println!("\n{}", "Reclaimable per filesystem".bold());
+        for (_, path, size) in by_device {
+            println!(
+                "  {:<40} {}",
+                format!("filesystem of {}", path.display()),
+                utils::format_size(size, self.cli.gb).cyan()
+            );
+        }
+    }
+
+    /// reclaimable bytes per filesystem, each named by the first path seen on it; the
+    /// cargo cache is counted once, on the filesystem of the cargo home
+    fn reclaimable_by_filesystem(
+        projects: &[RustProject],
+        cargo_home: Option<&Path>,
+    ) -> Vec<(Option<u64>, PathBuf, u64)> {
+        let mut by_device: Vec<(Option<u64>, PathBuf, u64)> = Vec::new();
+        let mut add = |device: Option<u64>, path: &Path, size: u64| {
+            match by_device.iter_mut().find(|(d, _, _)| *d == device) {
+                Some((_, _, total)) => *total += size,
+                None => by_device.push((device, path.to_path_buf(), size)),
+            }
+        };
+        for project in projects {
+            for (device, size) in project.reclaimable_by_device() {
+                let path = if device == project.target_device && project.target_on_other_filesystem() {
+                    project.target_dir.as_deref().unwrap_or(&project.path)
+                } else {
+                    &project.path
+                };
+                add(device, path, size);
+            }
+        }
+
+        let cache_size = Self::cargo_cache_size(projects);
+        if let Some(cargo_home) = cargo_home.filter(|_| cache_size > 0) {
+            add(utils::device_id(cargo_home), cargo_home, cache_size);
+        }
+        by_device
+    }
+
+    /// the shared cargo cache, carried by every project but counted once
+    pub fn cargo_cache_size(projects: &[RustProject]) -> u64 {
+        projects.first().map_or(0, |p| p.cargo_cache_size)
+    }
+
+    /// the cargo cache is shared, so its breakdown is printed once rather than per project
+    fn print_cargo_cache_breakdown(&self, project: &RustProject) {
+        let cache_artifacts: Vec<_> = project
+            .build_artifacts
+            .iter()
+            .filter(|a| matches!(a.artifact_type, ArtifactType::CargoRegistry | ArtifactType::CargoGitCache))
+            .collect();
+
+        if cache_artifacts.is_empty() {
+            return;
+        }
+
+        println!("\n{}", "Cargo cache".bold());
+        for artifact in cache_artifacts {
+            println!(
+                "  {:<40} {}",
+                artifact.label.as_deref().unwrap_or_default(),
+                utils::format_size(artifact.size, self.cli.gb).cyan()
+            );
+        }
+    }
+
+    /// every project with its bytes per artifact type, plus those totals across all projects
+    pub fn print_projects_json(&self, projects: &[RustProject]) -> Result<()> {
+        #[derive(Serialize)]
+        struct JsonOutput {
+            projects: Vec<JsonProject>,
+            artifact_totals: BTreeMap<ArtifactType, u64>,
+        }
+
+        let mut artifact_totals = BTreeMap::new();
+        let projects = projects
+            .iter()
+            .map(|project| {
+                let project = self.json_project(project.clone());
+                for (artifact_type, size) in &project.artifact_sizes {
+                    let total = artifact_totals.entry(artifact_type.clone()).or_default();
+                    // every project lists the same cargo cache
+                    if matches!(artifact_type, ArtifactType::CargoRegistry | ArtifactType::CargoGitCache) {
+                        *total = *size;
+                    } else {
+                        *total += size;
+                    }
+                }
+                project
+            })
+            .collect();
+
+        println!("{}", serde_json::to_string_pretty(&JsonOutput { projects, artifact_totals })?);
+        Ok(())
+    }
+
+    /// scan and print each project as a JSON line as soon as it's found, unsorted and
+    /// without holding the project list; returns how many projects were found
+    pub async fn stream_projects_jsonl(&self) -> Result<usize> {
+        let (mut receiver, handle) = self.scan_streaming();
+        while let Some(project) = receiver.recv().await {
+            println!("{}", serde_json::to_string(&self.json_project(project))?);
+        }
+
+        let stats = handle.await??;
+        if stats.timed_out {
+            eprintln!("{}", "Scan timed out, the results are incomplete".yellow());
+        }
+        self.print_scan_stats(&stats);
+        Ok(stats.projects_found)
+    }
+
+    fn json_project(&self, mut project: RustProject) -> JsonProject {
+        let artifact_sizes = project.artifact_sizes();
+        // raw paths, JSON escapes control characters on its own
+        project.path = utils::styled_path(&project.path, &self.cli.paths);
+        project.target_dir = project
+            .target_dir
+            .map(|dir| utils::styled_path(&dir, &self.cli.paths));
+        if let Some(largest) = &mut project.largest_file {
+            largest.path = utils::styled_path(&largest.path, &self.cli.paths);
+        }
+        JsonProject { project, artifact_sizes }
+    }
+
+    /// roll projects up by the first `depth` components of their path below the search
+    /// directory, largest group first
+    fn group_by_parent(projects: &[RustProject], search_dir: &Path, depth: usize) -> Vec<(PathBuf, usize, u64)> {
+        let mut groups: Vec<(PathBuf, usize, u64)> = Vec::new();
+        for project in projects {
+            let relative = project.path.strip_prefix(search_dir).unwrap_or(&project.path);
+            let group = search_dir.join(relative.components().take(depth).collect::<PathBuf>());
+            match groups.iter_mut().find(|(path, _, _)| *path == group) {
+                Some((_, count, size)) => {
+                    *count += 1;
+                    *size += project.total_cleanable_size();
+                }
+                None => groups.push((group, 1, project.total_cleanable_size())),
+            }
+        }
+
+        groups.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        groups
+    }
+
+    fn print_group_table(&self, projects: &[RustProject], depth: usize) {
+        println!("\n{:<50} {:>10} {:>15}", "Group".bold(), "Projects".bold(), "Size".bold());
+        println!("{}", "─".repeat(77));
+
+        for (group, count, size) in Self::group_by_parent(projects, &self.cli.get_search_directory(), depth) {
+            let size_str = if self.cli.no_sizes {
+                "unknown".to_string()
+            } else {
+                utils::format_size(size, self.cli.gb)
+            };
+            println!(
+                "{:<50} {:>10} {:>15}",
+                utils::display_path(&group, &self.cli.paths),
+                count,
+                size_str.cyan()
+            );
+        }
+    }
+
+    fn print_bar_list(&self, projects: &[RustProject]) {
+        let largest = projects.iter().map(|p| p.total_cleanable_size()).max().unwrap_or(0);
+        println!();
+        for project in projects {
+            let size = project.total_cleanable_size();
+            println!(
+                "{} {:>12}  {}",
+                utils::size_bar(size, largest, 20).cyan(),
+                project.format_size(self.cli.gb),
+                project.display_name()
+            );
+        }
+    }
+
+    fn print_project_table(&self, projects: &[RustProject]) {
+        let last_mod_width = if matches!(self.cli.timestamps, Some(TimestampFormat::Iso)) { 22 } else { 15 };
+
+        print!(
+            "\n{:<30} {:<15} {:<10} {:<20} {:<last_mod_width$} {:<12}",
+            "Project Name".bold(),
+            "Size".bold(),
+            "Kind".bold(),
+            "Path".bold(),
+            "Last Modified".bold(),
+            "Status".bold()
+        );
+        // column widths, the separator spans them and the single spaces between
+        let mut columns = vec![30, 15, 10, 20, last_mod_width, 12];
+        if self.cli.rebuild_cost {
+            print!(" {:<10} {:<12}", "Rebuild".bold(), "Build".bold());
+            columns.extend([10, 12]);
+        }
+        if self.cli.show_percent {
+            print!(" {:>9} {:>7}", "Reclaim %".bold(), "Disk %".bold());
+            columns.extend([9, 7]);
+        }
+        println!();
+        println!("{}", "─".repeat(columns.iter().sum::<usize>() + columns.len() - 1));
+
+        let total_reclaimable: u64 = projects.iter().map(|p| p.total_cleanable_size()).sum();
+        // one `df` per filesystem
+        let mut capacities: HashMap<Option<u64>, Option<u64>> = HashMap::new();
+
+        for project in projects {
+            let size_str = match project.size_label(self.cli.gb) {
+                label if project.size_known && project.total_cleanable_size() == 0 => label.dimmed(),
+                label => label.cyan(),
+            };
+            let path_display = utils::truncate_string(&utils::display_path(&project.path, &self.cli.paths), 20);
+
+            let last_mod = utils::format_timestamp(
+                project.last_modified,
+                self.cli.timestamps.as_ref().unwrap_or(&TimestampFormat::Date),
+            );
+
+            let status = project.status(DEFAULT_STALE_DAYS);
+            let status = match status {
+                ProjectStatus::Active => status.label().green(),
+                ProjectStatus::Stale => status.label().yellow(),
+                ProjectStatus::EmptyTarget => status.label().blue(),
+                ProjectStatus::NoTarget => status.label().dimmed(),
+                ProjectStatus::Protected => status.label().magenta(),
+                ProjectStatus::Workspace => status.label().cyan(),
+            };
+
+            print!(
+                "{:<30} {:<15} {:<10} {:<20} {:<last_mod_width$} {:<12}",
+                project.display_name(),
+                size_str,
+                project.crate_kind.map_or("-", |kind| kind.label()),
+                path_display,
+                last_mod,
+                status
+            );
+            if self.cli.rebuild_cost {
+                let freshness = match project.build_freshness() {
+                    Some(BuildFreshness::Stale) => BuildFreshness::Stale.label().yellow(),
+                    Some(BuildFreshness::Fresh) => BuildFreshness::Fresh.label().green(),
+                    None => "-".normal(),
+                };
+                print!(" {:<10} {:<12}", project.rebuild_cost().label(), freshness);
+            }
+            if self.cli.show_percent {
+                let capacity = *capacities
+                    .entry(project.device)
+                    .or_insert_with(|| utils::disk_capacity(&project.path));
+                print!(
+                    " {:>9} {:>7}",
+                    utils::format_percent(project.total_cleanable_size(), Some(total_reclaimable)),
+                    utils::format_percent(project.total_cleanable_size(), capacity)
+                );
+            }
+            println!();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{CleanScope, RebuildCost};
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    /// builds a temporary directory tree of synthetic Rust projects
+    struct ProjectTree {
+        root: TempDir,
+    }
+
+    impl ProjectTree {
+        fn new() -> Self {
+            Self { root: tempfile::tempdir().expect("failed to create temp dir") }
+        }
+
+        fn path(&self) -> &Path {
+            self.root.path()
+        }
+
+        /// add a crate with a `[package]` manifest and the given extra manifest content
+        fn package(self, dir: &str, name: &str, extra: &str) -> Self {
+            let manifest = format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\n{}", name, extra);
+            self.manifest(dir, &manifest)
+        }
+
+        /// add a `Cargo.toml` with arbitrary content
+        fn manifest(self, dir: &str, content: &str) -> Self {
+            self.file_with(&format!("{}/Cargo.toml", dir), content.as_bytes())
+        }
+
+        /// add a file of `size` bytes
+        fn file(self, path: &str, size: usize) -> Self {
+            self.file_with(path, &vec![0u8; size])
+        }
+
+        fn file_with(self, path: &str, content: &[u8]) -> Self {
+            let full_path = self.root.path().join(path);
+            fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+            fs::write(full_path, content).unwrap();
+            self
+        }
+
+        fn cli(&self, extra_args: &[&str]) -> Cli {
+            let dir = self.path().to_string_lossy().to_string();
+            Cli::parse_from(["rskill", "-d", dir.as_str()].iter().chain(extra_args))
+        }
+
+        fn scan(&self, extra_args: &[&str]) -> Vec<RustProject> {
+            let cli = self.cli(extra_args);
+            let mut stats = ScanStats::default();
+            ProjectScanner::find_rust_projects(self.path(), &cli.get_excluded_dirs(), &cli, &mut stats, None).unwrap()
+        }
+    }
+
+    fn names(projects: &[RustProject]) -> Vec<&str> {
+        projects.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    fn find<'a>(projects: &'a [RustProject], name: &str) -> &'a RustProject {
+        projects.iter().find(|p| p.name == name).expect("project not found")
+    }
+
+    #[test]
+    fn test_finds_projects_with_target_sizes() {
+        let tree = ProjectTree::new()
+            .package("small", "small", "")
+            .file("small/target/debug/deps/libsmall.rlib", 1_000)
+            .package("big", "big", "")
+            .file("big/target/debug/deps/libbig.rlib", 5_000)
+            .file("big/target/debug/big", 3_000)
+            .package("clean", "clean", "");
+
+        let projects = tree.scan(&[]);
+
+        assert_eq!(names(&projects), ["big", "small", "clean"]);
+        assert_eq!(find(&projects, "big").target_size, 8_000);
+        assert_eq!(find(&projects, "small").target_size, 1_000);
+
+        let clean = find(&projects, "clean");
+        assert_eq!(clean.target_size, 0);
+        assert!(clean.target_dir.is_none());
+    }
+
+    #[test]
+    fn test_classifies_build_artifacts() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/target/debug/deps/libdep.rlib", 100)
+            .file("app/target/debug/incremental/app-1/s-1/query-cache.bin", 200)
+            .file("app/target/debug/examples/demo", 300)
+            .file("app/target/release/app", 400);
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+
+        let artifact_size = |dir: &str| {
+            let path: PathBuf = tree.path().join("app/target").join(dir);
+            app.build_artifacts
+                .iter()
+                .find(|a| a.path == path)
+                .map(|a| (a.size, format!("{:?}", a.artifact_type)))
+        };
+
+        assert_eq!(artifact_size("debug/deps"), Some((100, "Dependencies".to_string())));
+        assert_eq!(artifact_size("debug/incremental"), Some((200, "IncrementalCompilation".to_string())));
+        assert_eq!(artifact_size("debug/examples"), Some((300, "Examples".to_string())));
+        assert_eq!(artifact_size("debug"), Some((600, "Target".to_string())));
+        assert_eq!(artifact_size("release"), Some((400, "Target".to_string())));
+    }
+
+    #[test]
+    fn test_custom_artifact_patterns() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/target/debug/app", 100)
+            .file("app/target/wasm32-unknown-unknown/release/app.wasm", 200)
+            .file("app/dist/index.html", 50)
+            .file("app/src/dist/keep.rs", 10);
+
+        let mut cli = tree.cli(&[]);
+        cli.config = toml::from_str(
+            r#"
+            [[artifacts]]
+            pattern = "dist"
+
+            [[artifacts]]
+            pattern = "target/wasm32-*"
+            type = "Examples"
+            "#,
+        )
+        .unwrap();
+        let mut stats = ScanStats::default();
+        let projects =
+            ProjectScanner::find_rust_projects(tree.path(), &[], &cli, &mut stats, None).unwrap();
+        let app = find(&projects, "app");
+
+        let labels: Vec<_> = app.build_artifacts.iter().filter_map(|a| a.label.as_deref()).collect();
+        assert_eq!(labels, ["dist", "target/wasm32-*"]);
+
+        // the wasm directory is inside target, only dist is cleaned separately
+        assert_eq!(app.extra_artifacts, [tree.path().join("app/dist")]);
+        assert_eq!(app.total_cleanable_size(), 350);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_target_is_only_unlinked() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("shared-cache/debug/app", 100);
+        std::os::unix::fs::symlink(tree.path().join("shared-cache"), tree.path().join("app/target")).unwrap();
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+        let skip_symlinked = CleanScope { skip_symlinked_targets: true, ..CleanScope::default() };
+
+        assert!(app.target_symlink);
+        assert_eq!(app.cleanable_dirs(CleanScope::default()), [tree.path().join("app/target")]);
+        assert_eq!(app.cleanable_size(CleanScope::default()), 0);
+        assert!(app.cleanable_dirs(skip_symlinked).is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shared_targets() {
+        let tree = ProjectTree::new()
+            .package("a", "a", "")
+            .package("b", "b", "")
+            .package("c", "c", "")
+            .file("shared-cache/debug/app", 100)
+            .file("c/target/debug/c", 10);
+        for project in ["a", "b"] {
+            std::os::unix::fs::symlink(tree.path().join("shared-cache"), tree.path().join(project).join("target")).unwrap();
+        }
+
+        let projects = tree.scan(&[]);
+        let shared = ProjectScanner::shared_targets(&projects);
+
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].0, tree.path().join("shared-cache").canonicalize().unwrap());
+        let mut sharing: Vec<_> = shared[0].1.iter().map(|p| p.name.as_str()).collect();
+        sharing.sort();
+        assert_eq!(sharing, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_shared_targets_include_workspace_members() {
+        let tree = ProjectTree::new()
+            .manifest("ws", "[workspace]\nmembers = [\"one\", \"two\"]\n")
+            .package("ws/one", "one", "")
+            .package("ws/two", "two", "")
+            .file("ws/target/debug/one", 100);
+
+        let projects = tree.scan(&[]);
+        let shared = ProjectScanner::shared_targets(&projects);
+
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].0, tree.path().join("ws/target").canonicalize().unwrap());
+        let mut sharing: Vec<_> = shared[0].1.iter().map(|p| p.name.as_str()).collect();
+        sharing.sort();
+        assert_eq!(sharing, ["one", "two", "ws"]);
+    }
+
+    #[test]
+    fn test_safe_mode_keeps_cache_typed_artifacts() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/target/debug/app", 100)
+            .file("app/dist/index.html", 50)
+            .file("app/vendor/registry/crate.tar", 20);
+
+        let mut cli = tree.cli(&[]);
+        cli.config = toml::from_str(
+            r#"
+            [[artifacts]]
+            pattern = "dist"
+
+            [[artifacts]]
+            pattern = "vendor"
+            type = "CargoRegistry"
+            "#,
+        )
+        .unwrap();
+        let mut stats = ScanStats::default();
+        let projects =
+            ProjectScanner::find_rust_projects(tree.path(), &[], &cli, &mut stats, None).unwrap();
+        let app = find(&projects, "app");
+
+        assert_eq!(app.cleanable_dirs(CleanScope::default()).len(), 3);
+        assert_eq!(
+            app.cleanable_dirs(CleanScope { safe_only: true, ..CleanScope::default() }),
+            [tree.path().join("app/target"), tree.path().join("app/dist")]
+        );
+    }
+
+    #[test]
+    fn test_heaviest_subtree_skips_projects_and_targets() {
+        let mut tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/frontend/package.json", 1);
+        for i in 0..20 {
+            tree = tree
+                .file(&format!("app/target/debug/deps/{}.o", i), 1)
+                .file(&format!("app/frontend/node_modules/pkg{}/index.js", i), 1);
+        }
+
+        let cli = tree.cli(&[]);
+        let mut stats = ScanStats::default();
+        ProjectScanner::find_rust_projects(tree.path(), &[], &cli, &mut stats, None).unwrap();
+
+        // 20 package directories plus the file in each
+        assert_eq!(stats.heaviest_subtree, Some((tree.path().join("app/frontend/node_modules"), 40)));
+    }
+
+    #[test]
+    fn test_own_dirs_below_the_search_directory() {
+        let own = [PathBuf::from("/home/me/.config/rskill"), PathBuf::from("/var/cache/rskill")];
+
+        assert_eq!(
+            ProjectScanner::own_dirs_below(Path::new("/home/me"), &own),
+            [PathBuf::from("/home/me/.config/rskill")]
+        );
+        assert_eq!(
+            ProjectScanner::own_dirs_below(Path::new("/"), &own),
+            [PathBuf::from("/home/me/.config/rskill"), PathBuf::from("/var/cache/rskill")]
+        );
+        assert!(ProjectScanner::own_dirs_below(Path::new("/srv"), &own).is_empty());
+    }
+
+    #[test]
+    fn test_configured_target_dir_resolution() {
+        let tree = ProjectTree::new()
+            .manifest("ws", "[workspace]\nmembers = [\"member\"]\n")
+            .package("ws/member", "member", "")
+            .file_with("ws/member/.cargo/config.toml", b"[build]\ntarget-dir = \"../shared-target\"\n")
+            .file("ws/shared-target/debug/member", 40)
+            .package("ws/other", "other", "")
+            .file_with("ws/.cargo/config.toml", b"[build]\ntarget-dir = \"out\"\n")
+            .package("abs", "abs", "")
+            .file("elsewhere/debug/abs", 10);
+        let absolute = tree.path().join("elsewhere");
+        fs::create_dir_all(tree.path().join("abs/.cargo")).unwrap();
+        fs::write(
+            tree.path().join("abs/.cargo/config.toml"),
+            format!("[build]\ntarget-dir = {:?}\n", absolute.display().to_string()),
+        )
+        .unwrap();
+
+        // relative to the directory holding `.cargo`, the nearest config wins
+        assert_eq!(
+            ProjectScanner::configured_target_dir(&tree.path().join("ws/member")),
+            Some(tree.path().join("ws/shared-target"))
+        );
+        assert_eq!(
+            ProjectScanner::configured_target_dir(&tree.path().join("ws/other")),
+            Some(tree.path().join("ws/out"))
+        );
+        assert_eq!(ProjectScanner::configured_target_dir(&tree.path().join("abs")), Some(absolute));
+
+        let projects = tree.scan(&[]);
+        assert_eq!(find(&projects, "member").target_size, 40);
+        assert_eq!(find(&projects, "abs").target_size, 10);
+        assert!(find(&projects, "member").shared_target);
+        assert!(find(&projects, "abs").shared_target);
+        // an explicit --target name ignores the config
+        assert_eq!(find(&tree.scan(&["--target", "build"]), "member").target_dir, None);
+    }
+
+    #[test]
+    fn test_pinned_toolchain_files() {
+        let tree = ProjectTree::new()
+            .package("toml", "toml", "")
+            .file_with("toml/rust-toolchain.toml", b"[toolchain]\nchannel = \"1.75.0\"\ncomponents = [\"clippy\"]\n")
+            .package("legacy", "legacy", "")
+            .file_with("legacy/rust-toolchain", b"nightly-2024-01-01\n")
+            .package("unpinned", "unpinned", "");
+
+        assert_eq!(ProjectScanner::pinned_toolchain(&tree.path().join("toml")).as_deref(), Some("1.75.0"));
+        assert_eq!(ProjectScanner::pinned_toolchain(&tree.path().join("legacy")).as_deref(), Some("nightly-2024-01-01"));
+        assert_eq!(ProjectScanner::pinned_toolchain(&tree.path().join("unpinned")), None);
+    }
+
+    #[test]
+    fn test_resolve_config_path_expands_home() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(ProjectScanner::resolve_config_path(Path::new("/ws"), "~/targets"), home.join("targets"));
+        assert_eq!(ProjectScanner::resolve_config_path(Path::new("/ws"), "/tmp/t"), PathBuf::from("/tmp/t"));
+        assert_eq!(ProjectScanner::resolve_config_path(Path::new("/ws"), "t"), PathBuf::from("/ws/t"));
+    }
+
+    #[test]
+    fn test_redundant_registry_sources_keep_unarchived_ones() {
+        let tree = ProjectTree::new()
+            .file("cargo/registry/cache/index.crates.io-1/serde-1.0.0.crate", 10)
+            .file("cargo/registry/src/index.crates.io-1/serde-1.0.0/src/lib.rs", 30)
+            .file("cargo/registry/src/index.crates.io-1/local-0.1.0/src/lib.rs", 20);
+
+        let sources = ProjectScanner::redundant_registry_sources(&tree.path().join("cargo"), false).unwrap();
+
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].label.as_deref(), Some("serde-1.0.0"));
+        assert_eq!(sources[0].size, 30);
+    }
+
+    #[test]
+    fn test_vendored_deps_need_a_source_replacement() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/vendor/serde/lib.rs", 300)
+            .file_with(
+                "app/.cargo/config.toml",
+                b"[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = \"vendor\"\n",
+            )
+            .package("plain", "plain", "")
+            .file("plain/vendor/notes.txt", 10);
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+        let vendor = tree.path().join("app/vendor");
+
+        assert_eq!(app.extra_artifacts, std::slice::from_ref(&vendor));
+        assert!(app.is_vendored_deps(&vendor));
+        assert_eq!(app.extra_artifacts_size, 300);
+        assert_eq!(
+            app.cleanable_dirs(CleanScope { safe_only: true, ..CleanScope::default() }),
+            Vec::<PathBuf>::new()
+        );
+        assert!(find(&projects, "plain").extra_artifacts.is_empty());
+    }
+
+    #[test]
+    fn test_keep_docs_cleans_around_documentation() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/target/debug/deps/libdep.rlib", 100)
+            .file("app/target/doc/app/index.html", 40)
+            .file("app/target/wasm32-unknown-unknown/doc/app/index.html", 20)
+            .file("app/target/wasm32-unknown-unknown/release/app.wasm", 30);
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+        let target = tree.path().join("app/target");
+        let keep_docs = CleanScope { keep_docs: true, ..CleanScope::default() };
+
+        assert_eq!(app.cleanable_dirs(CleanScope::default()), [tree.path().join("app/target")]);
+        assert_eq!(
+            app.cleanable_dirs(keep_docs),
+            [target.join("debug"), target.join("wasm32-unknown-unknown/release")]
+        );
+        assert_eq!(app.cleanable_size(keep_docs), 130);
+    }
+
+    #[test]
+    fn test_cleanable_size_leaves_out_the_cargo_cache() {
+        let project = RustProject { cargo_cache_size: 5_000, ..RustProject::fixture("/src/app", 100) };
+
+        assert_eq!(project.total_cleanable_size(), 100);
+        assert_eq!(project.cleanable_size(CleanScope::default()), 100);
+        let projects = [project.clone(), RustProject { path: "/src/lib".into(), ..project }];
+        assert_eq!(ProjectScanner::cargo_cache_size(&projects), 5_000);
+    }
+
+    #[test]
+    fn test_last_modified_considers_nested_sources() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .file("app/src/bin/tool.rs", 10);
+
+        let old = std::time::SystemTime::now() - Duration::from_secs(90 * 24 * 60 * 60);
+        fs::File::options()
+            .write(true)
+            .open(tree.path().join("app/Cargo.toml"))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let projects = tree.scan(&[]);
+        assert!(find(&projects, "app").is_likely_active(DEFAULT_STALE_DAYS));
+    }
+
+    #[test]
+    fn test_last_modified_considers_a_custom_lib_path() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "[lib]\npath = \"code/lib.rs\"\n")
+            .file("app/code/lib.rs", 10)
+            .file("app/code/parser/mod.rs", 10);
+
+        let old = std::time::SystemTime::now() - Duration::from_secs(90 * 24 * 60 * 60);
+        for file in ["app/Cargo.toml", "app/code/lib.rs"] {
+            fs::File::options().write(true).open(tree.path().join(file)).unwrap().set_modified(old).unwrap();
+        }
+
+        let projects = tree.scan(&[]);
+        assert!(find(&projects, "app").is_likely_active(DEFAULT_STALE_DAYS));
+    }
+
+    #[test]
+    fn test_cargo_cache_counts_on_the_cargo_home_filesystem() {
+        let cargo_home = tempfile::tempdir().unwrap();
+        let projects = [
+            RustProject { device: Some(u64::MAX), cargo_cache_size: 500, ..RustProject::fixture("/src/app", 100) },
+            RustProject { device: Some(u64::MAX), cargo_cache_size: 500, ..RustProject::fixture("/src/lib", 20) },
+        ];
+
+        let by_device = ProjectScanner::reclaimable_by_filesystem(&projects, Some(cargo_home.path()));
+        assert_eq!(
+            by_device,
+            [
+                (Some(u64::MAX), PathBuf::from("/src/app"), 120),
+                (utils::device_id(cargo_home.path()), cargo_home.path().to_path_buf(), 500),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classifies_only_expected_layout() {
+        let classify = |path: &str| {
+            ProjectScanner::classify_target_path(Path::new("/nonexistent/target"), Path::new(path))
+                .map(|t| format!("{:?}", t))
+        };
+
+        assert_eq!(classify("debug").as_deref(), Some("Target"));
+        assert_eq!(classify("release/deps").as_deref(), Some("Dependencies"));
+        assert_eq!(classify("x86_64-unknown-linux-gnu/debug/incremental").as_deref(), Some("IncrementalCompilation"));
+        assert_eq!(classify("doc").as_deref(), Some("Documentation"));
+        assert_eq!(classify("wasm32-unknown-unknown/doc").as_deref(), Some("Documentation"));
+        assert_eq!(classify("doc/examples"), None);
+        assert_eq!(classify("debug/build/debug"), None);
+        assert_eq!(classify("tmp/debug"), None);
+        assert_eq!(classify("ci"), None);
+    }
+
+    #[test]
+    fn test_custom_profiles_are_targets() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "[profile.ci]\ninherits = \"release\"\n")
+            .file("app/target/ci/deps/libdep.rlib", 100)
+            .file("app/target/ci/app", 20)
+            .file("app/target/x86_64-unknown-linux-gnu/fast-dev-build/deps/libdep.rlib", 30)
+            .file("app/target/tmp/scratch", 5);
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+        let sizes = app.artifact_sizes();
+
+        assert_eq!(sizes[&ArtifactType::Target], 150);
+        assert_eq!(sizes[&ArtifactType::Dependencies], 130);
+    }
+
+    #[test]
+    fn test_detects_crate_kind() {
+        let tree = ProjectTree::new()
+            .package("tool", "tool", "")
+            .file("tool/src/main.rs", 10)
+            .package("helpers", "helpers", "")
+            .file("helpers/src/lib.rs", 10)
+            .package("both", "both", "[[bin]]\nname = \"cli\"\npath = \"cli.rs\"\n")
+            .file("both/src/lib.rs", 10)
+            .manifest("ws", "[workspace]\nmembers = []\n")
+            .package("empty", "empty", "");
+
+        let projects = tree.scan(&[]);
+        let kind = |name: &str| find(&projects, name).crate_kind;
+
+        assert_eq!(kind("tool"), Some(CrateKind::Bin));
+        assert_eq!(kind("helpers"), Some(CrateKind::Lib));
+        assert_eq!(kind("both"), Some(CrateKind::Both));
+        assert_eq!(kind("ws"), Some(CrateKind::Workspace));
+        assert_eq!(kind("empty"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_control_characters_in_paths_are_escaped() {
+        let tree = ProjectTree::new()
+            .manifest("evil\nname", "[package]\nname = \"evil\"\n")
+            .file("evil\nname/target/debug/app", 10);
+
+        let projects = tree.scan(&[]);
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].target_dir, Some(tree.path().join("evil\nname/target")));
+        assert!(utils::escape_path(&projects[0].path).ends_with("evil\\nname"));
+    }
+
+    #[test]
+    fn test_duplicate_deps_size() {
+        let tree = ProjectTree::new()
+            .package("a", "a", "")
+            .file("a/target/debug/deps/libserde-1234.rlib", 100)
+            .file("a/target/debug/deps/liba-0001.rlib", 10)
+            .package("b", "b", "")
+            .file("b/target/debug/deps/libserde-1234.rlib", 100)
+            .file("b/target/release/deps/libserde-1234.rlib", 100)
+            .file("b/target/debug/deps/libserde-5678.rlib", 100);
+
+        // b's release copy duplicates its own debug one, which sharing a target doesn't save
+        let projects = tree.scan(&[]);
+        assert_eq!(ProjectScanner::duplicate_deps_size(&projects, false), 100);
+    }
+
+    #[test]
+    fn test_timeout_stops_the_walk() {
+        let tree = ProjectTree::new().package("app", "app", "");
+
+        let cli = tree.cli(&["--timeout", "0"]);
+        let mut stats = ScanStats::default();
+        let projects =
+            ProjectScanner::find_rust_projects(tree.path(), &[], &cli, &mut stats, None).unwrap();
+
+        assert!(projects.is_empty());
+        assert!(stats.timed_out);
+    }
+
+    #[test]
+    fn test_max_results_keeps_largest() {
+        let tree = ProjectTree::new()
+            .package("small", "small", "")
+            .file("small/target/debug/a", 10)
+            .package("large", "large", "")
+            .file("large/target/debug/a", 300)
+            .package("medium", "medium", "")
+            .file("medium/target/debug/a", 200);
+
+        let projects = tree.scan(&["--max-results", "2", "--sort", "path"]);
+        assert_eq!(names(&projects), ["large", "medium"]);
+    }
+
+    #[test]
+    fn test_equal_sizes_sorted_by_path() {
+        let tree = ProjectTree::new()
+            .package("b", "b", "")
+            .file("b/target/debug/a", 100)
+            .package("c", "c", "")
+            .file("c/target/debug/a", 100)
+            .package("a", "a", "")
+            .file("a/target/debug/a", 100)
+            .package("big", "big", "")
+            .file("big/target/debug/a", 500);
+
+        assert_eq!(names(&tree.scan(&[])), ["big", "a", "b", "c"]);
+        assert_eq!(names(&tree.scan(&["--reverse"])), ["c", "b", "a", "big"]);
+    }
+
+    #[test]
+    fn test_resolves_inherited_package_fields() {
+        let tree = ProjectTree::new()
+            .manifest(".", "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"2.1.0\"\n")
+            .manifest("crates/core", "[package]\nname = \"ws-core\"\nversion.workspace = true\n");
+
+        let projects = tree.scan(&[]);
+        assert_eq!(find(&projects, "ws-core").version.as_deref(), Some("2.1.0"));
+    }
+
+    #[test]
+    fn test_group_by_parent() {
+        let tree = ProjectTree::new()
+            .package("work/client-a/api", "api", "")
+            .file("work/client-a/api/target/debug/a", 100)
+            .package("work/client-a/web", "web", "")
+            .file("work/client-a/web/target/debug/a", 200)
+            .package("work/client-b/tool", "tool", "")
+            .file("work/client-b/tool/target/debug/a", 50)
+            .package("scratch", "scratch", "");
+
+        let projects = tree.scan(&[]);
+        let groups = ProjectScanner::group_by_parent(&projects, tree.path(), 2);
+        let root = tree.path();
+        assert_eq!(
+            groups,
+            vec![
+                (root.join("work/client-a"), 2, 300),
+                (root.join("work/client-b"), 1, 50),
+                (root.join("scratch"), 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_freshness_from_fingerprints() {
+        let tree = ProjectTree::new()
+            .package("fresh", "fresh", "")
+            .file("fresh/target/debug/.fingerprint/fresh-1/bin-fresh", 10)
+            .package("stale", "stale", "")
+            .file("stale/target/x86_64-unknown-linux-gnu/release/.fingerprint/stale-1/bin-stale", 10)
+            .package("unbuilt", "unbuilt", "")
+            .file("unbuilt/target/debug/stale", 10);
+        let touch = |path: &str, time: std::time::SystemTime| {
+            fs::File::options().write(true).open(tree.path().join(path)).unwrap().set_modified(time).unwrap();
+        };
+        let hour = Duration::from_secs(3600);
+        touch("fresh/target/debug/.fingerprint/fresh-1/bin-fresh", std::time::SystemTime::now() + hour);
+        // edited after the build
+        touch("stale/Cargo.toml", std::time::SystemTime::now() + hour);
+
+        let projects = tree.scan(&[]);
+        assert_eq!(find(&projects, "fresh").build_freshness(), Some(BuildFreshness::Fresh));
+        assert_eq!(find(&projects, "stale").build_freshness(), Some(BuildFreshness::Stale));
+        assert_eq!(find(&projects, "unbuilt").build_freshness(), None);
+    }
+
+    #[test]
+    fn test_age_histogram() {
+        let tree = ProjectTree::new()
+            .package("new", "new", "")
+            .file("new/target/debug/new", 10)
+            .package("old", "old", "")
+            .file("old/target/debug/old", 30);
+        let old_time = std::time::SystemTime::now() - Duration::from_secs(100 * 24 * 3600);
+        fs::File::options()
+            .write(true)
+            .open(tree.path().join("old/Cargo.toml"))
+            .unwrap()
+            .set_modified(old_time)
+            .unwrap();
+
+        let histogram = ProjectScanner::age_histogram(&tree.scan(&[]));
+
+        assert_eq!(
+            histogram,
+            [("< 7d", 1, 10), ("7-30d", 0, 0), ("30-90d", 0, 0), ("90d+", 1, 30)]
+        );
+    }
+
+    #[test]
+    fn test_age_histogram_bucket_boundaries() {
+        let mut tree = ProjectTree::new();
+        for (name, size) in [("d6", 1), ("d7", 2), ("d30", 4), ("d90", 8), ("unknown", 16)] {
+            tree = tree.package(name, name, "").file(&format!("{}/target/debug/{}", name, name), size);
+        }
+        let mut projects = tree.scan(&[]);
+        for project in &mut projects {
+            project.last_modified = match project.name.as_str() {
+                "d6" => Some(Utc::now() - chrono::Duration::days(6)),
+                "d7" => Some(Utc::now() - chrono::Duration::days(7)),
+                "d30" => Some(Utc::now() - chrono::Duration::days(30)),
+                "d90" => Some(Utc::now() - chrono::Duration::days(90)),
+                _ => None,
+            };
+        }
+
+        assert_eq!(
+            ProjectScanner::age_histogram(&projects),
+            [("< 7d", 1, 1), ("7-30d", 1, 2), ("30-90d", 1, 4), ("90d+", 1, 8)]
+        );
+    }
+
+    #[test]
+    fn test_rebuild_consequence_wording() {
+        assert!(ArtifactType::IncrementalCompilation.rebuild_consequence().starts_with("fast to rebuild"));
+        assert!(ArtifactType::Dependencies.rebuild_consequence().starts_with("slow to rebuild"));
+        assert!(ArtifactType::Dependencies.rebuild_consequence().contains("every dependency is compiled again"));
+    }
+
+    #[test]
+    fn test_project_status() {
+        let tree = ProjectTree::new()
+            .package("built", "built", "")
+            .file("built/target/debug/built", 10)
+            .package("empty", "empty", "")
+            .file("empty/target/debug/.keep", 0)
+            .package("unbuilt", "unbuilt", "")
+            .manifest("ws", "[workspace]\nmembers = []\n")
+            .file("ws/target/debug/ws", 10)
+            .package("kept", "kept", "")
+            .file("kept/target/debug/kept", 10)
+            .file_with("kept/.rskillignore", b"");
+
+        let projects = tree.scan(&[]);
+        let status = |name: &str| find(&projects, name).status(DEFAULT_STALE_DAYS);
+        assert_eq!(status("built"), ProjectStatus::Active);
+        assert_eq!(status("empty"), ProjectStatus::EmptyTarget);
+        assert_eq!(status("unbuilt"), ProjectStatus::NoTarget);
+        assert_eq!(status("ws"), ProjectStatus::Workspace);
+        assert_eq!(status("kept"), ProjectStatus::Protected);
+    }
+
+    #[test]
+    fn test_rebuild_cost() {
+        let many_deps: String = (0..25).map(|i| format!("dep{} = \"1\"\n", i)).collect();
+        let tree = ProjectTree::new()
+            .package("small", "small", "[dependencies]\nserde = \"1\"\n")
+            .file("small/target/debug/deps/libserde.rlib", 10)
+            .package("medium", "medium", &format!("[dependencies]\n{}", many_deps))
+            .file("medium/target/debug/deps/libdep0.rlib", 10)
+            .package("incremental-only", "incremental-only", &format!("[dependencies]\n{}", many_deps))
+            .file("incremental-only/target/debug/incremental/s-1/a", 10);
+
+        let projects = tree.scan(&[]);
+        assert_eq!(find(&projects, "small").rebuild_cost(), RebuildCost::Cheap);
+        assert_eq!(find(&projects, "medium").rebuild_cost(), RebuildCost::Moderate);
+        assert_eq!(find(&projects, "incremental-only").rebuild_cost(), RebuildCost::Cheap);
+    }
+
+    #[test]
+    fn test_sort_by_path() {
+        let tree = ProjectTree::new()
+            .package("c", "c", "")
+            .package("a", "a", "")
+            .file("a/target/debug/a", 10)
+            .package("b", "b", "")
+            .file("b/target/debug/b", 1_000);
+
+        assert_eq!(names(&tree.scan(&["--sort", "path"])), ["a", "b", "c"]);
+        assert_eq!(names(&tree.scan(&["--sort", "size"])), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn test_nested_projects_and_workspaces() {
+        let tree = ProjectTree::new()
+            .manifest("ws", "[workspace]\nmembers = [\"crates/*\"]\n")
+            .package("ws/crates/one", "one", "")
+            .package("ws/crates/two", "two", "")
+            .package("ws/examples/nested", "nested", "");
+
+        let projects = tree.scan(&[]);
+        let ws = find(&projects, "ws");
+        assert!(ws.workspace_root);
+        assert!(ws.virtual_manifest);
+        assert_eq!(ws.workspace_members, 2);
+        assert!(!find(&projects, "one").workspace_root);
+        assert_eq!(projects.len(), 4);
+
+        let members_excluded = tree.scan(&["--exclude-workspace-members"]);
+        let mut members_excluded = names(&members_excluded);
+        members_excluded.sort();
+        assert_eq!(members_excluded, ["nested", "ws"]);
+    }
+
+    #[test]
+    fn test_workspace_detection_ignores_formatting() {
+        let tree = ProjectTree::new()
+            .manifest("ws", "workspace.members = [\n  \"one\", # the core\n]\n\n[package] # the root crate\nname = \"ws\"\n")
+            .package("ws/one", "one", "")
+            .manifest("docs", "# no [workspace] here\n[package]\nname = \"docs\"\n");
+
+        let projects = tree.scan(&[]);
+        let ws = find(&projects, "ws");
+        assert!(ws.workspace_root);
+        assert!(!ws.virtual_manifest);
+        assert_eq!(ws.workspace_members, 1);
+        assert!(!find(&projects, "docs").workspace_root);
+    }
+
+    #[test]
+    fn test_dependency_count() {
+        let tree = ProjectTree::new().package(
+            "app",
+            "app",
+            "\n[dependencies]\nserde = \"1\"\n# a comment\nanyhow = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n",
+        );
+
+        let projects = tree.scan(&[]);
+        assert_eq!(find(&projects, "app").dependencies_count, 3);
+    }
+
+    #[test]
+    fn test_dependency_count_ignores_formatting() {
+        let tree = ProjectTree::new().package(
+            "app",
+            "app",
+            r#"
+[dependencies]
+serde = { version = "1", features = [
+    "derive",
+] }
+anyhow.workspace = true
+
+[dependencies.tokio]
+version = "1"
+features = ["full"]
+
+[target.'cfg(unix)'.dependencies]
+libc = "0.2"
+serde = "1"
+
+[dev-dependencies]
+tempfile = { workspace = true }
+"#,
+        );
+
+        let projects = tree.scan(&[]);
+        let app = find(&projects, "app");
+        assert_eq!(app.dependencies_count, 5);
+        assert_eq!(app.workspace_dependencies_count, 2);
+    }
+
+    #[test]
+    fn test_no_sizes_skips_sizing_and_sorts_by_path() {
+        let tree = ProjectTree::new()
+            .package("b", "b", "")
+            .file("b/target/debug/b", 1_000)
+            .package("a", "a", "");
+
+        let projects = tree.scan(&["--no-sizes"]);
+        assert_eq!(names(&projects), ["a", "b"]);
+
+        let b = find(&projects, "b");
+        assert!(!b.size_known);
+        assert!(b.target_dir.is_some());
+        assert_eq!(b.format_size(false), "unknown");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_duplicate_counted_once() {
+        let tree = ProjectTree::new()
+            .package("real/app", "app", "")
+            .file("real/app/target/debug/app", 1_000);
+        std::os::unix::fs::symlink(tree.path().join("real"), tree.path().join("link")).unwrap();
+
+        let projects = tree.scan(&["--follow-links"]);
+        assert_eq!(names(&projects), ["app"]);
+    }
+
+    #[test]
+    fn test_verify_cargo_toml_skips_fragments() {
+        let tree = ProjectTree::new()
+            .package("app", "app", "")
+            .manifest("docs/snippet", "[dependencies]\nserde = \"1\"\n")
+            .manifest("broken", "[package\nname = ");
+
+        let projects = tree.scan(&["--verify-cargo-toml"]);
+        assert_eq!(names(&projects), ["app"]);
+        assert_eq!(tree.scan(&[]).len(), 3);
+    }
+
+    #[test]
+    fn test_excluded_dirs() {
+        let tree = ProjectTree::new()
+            .package("keep", "keep", "")
+            .package("vendor/skip", "skip", "");
+
+        assert_eq!(names(&tree.scan(&["--exclude", "vendor"])), ["keep"]);
+    }
+
+    #[test]
+    fn test_exclusions_ignore_the_search_directory() {
+        // temp dirs are named `.tmpXXXX`, a hidden search root
+        let tree = ProjectTree::new()
+            .package("keep", "keep", "")
+            .package(".hidden/skip", "skip", "");
+
+        assert_eq!(names(&tree.scan(&["--exclude-hidden"])), ["keep"]);
+        let projects = tree.scan(&["--exclude", "tmp"]);
+        let mut found = names(&projects);
+        found.sort();
+        assert_eq!(found, ["keep", "skip"]);
     }
 }
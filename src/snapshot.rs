@@ -0,0 +1,135 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use crate::{cli::PathStyle, project::RustProject, utils};
+
+/// a saved scan, written by `--snapshot` and read back by `--compare`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub created: DateTime<Utc>,
+    pub projects: Vec<RustProject>,
+}
+
+/// how a project's cleanable size changed between two scans
+#[derive(Debug, PartialEq, Eq)]
+pub enum SizeDelta {
+    Changed { path: PathBuf, before: u64, after: u64 },
+    New { path: PathBuf, size: u64 },
+    Removed { path: PathBuf, size: u64 },
+}
+
+impl SizeDelta {
+    fn signed_bytes(&self) -> i128 {
+        match self {
+            SizeDelta::Changed { before, after, .. } => *after as i128 - *before as i128,
+            SizeDelta::New { size, .. } => *size as i128,
+            SizeDelta::Removed { size, .. } => -(*size as i128),
+        }
+    }
+}
+
+impl Snapshot {
+    /// paths are stored absolute so snapshots compare across working directories
+    pub fn new(projects: &[RustProject]) -> Result<Self> {
+        let projects = projects
+            .iter()
+            .map(|p| {
+                let mut project = p.clone();
+                project.path = std::path::absolute(&p.path)?;
+                Ok(project)
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { created: Utc::now(), projects })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write snapshot {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid snapshot {}", path.display()))
+    }
+
+    /// changes from this snapshot to a newer one, largest growth first, unchanged projects left out
+    pub fn diff(&self, current: &Snapshot) -> Vec<SizeDelta> {
+        let mut previous: HashMap<&Path, u64> = self
+            .projects
+            .iter()
+            .map(|p| (p.path.as_path(), p.total_cleanable_size()))
+            .collect();
+
+        let mut deltas = Vec::new();
+        for project in &current.projects {
+            let after = project.total_cleanable_size();
+            match previous.remove(project.path.as_path()) {
+                Some(before) if before == after => {}
+                Some(before) => deltas.push(SizeDelta::Changed { path: project.path.clone(), before, after }),
+                None => deltas.push(SizeDelta::New { path: project.path.clone(), size: after }),
+            }
+        }
+        deltas.extend(
+            previous
+                .into_iter()
+                .map(|(path, size)| SizeDelta::Removed { path: path.to_path_buf(), size }),
+        );
+
+        deltas.sort_by_key(|d| std::cmp::Reverse(d.signed_bytes()));
+        deltas
+    }
+}
+
+/// print the per-project changes and the overall change since the snapshot
+pub fn print_comparison(previous: &Snapshot, current: &Snapshot, use_gb: bool, paths: &PathStyle) {
+    let deltas = previous.diff(current);
+    let signed_size = |bytes: i128| {
+        let size = utils::format_size(bytes.unsigned_abs() as u64, use_gb);
+        if bytes < 0 { format!("-{}", size) } else { format!("+{}", size) }
+    };
+
+    for delta in &deltas {
+        let (path, change) = match delta {
+            SizeDelta::Changed { path, .. } => (path, signed_size(delta.signed_bytes()).normal()),
+            SizeDelta::New { path, .. } => (path, format!("{} (new)", signed_size(delta.signed_bytes())).cyan()),
+            SizeDelta::Removed { path, .. } => (path, format!("{} (removed)", signed_size(delta.signed_bytes())).dimmed()),
+        };
+        let change = if delta.signed_bytes() > 0 { change.yellow() } else { change };
+        println!("{:<24} {}", change, utils::display_path(path, paths));
+    }
+
+    let total: i128 = deltas.iter().map(SizeDelta::signed_bytes).sum();
+    println!(
+        "\n{} since {}",
+        signed_size(total).bold(),
+        previous.created.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(projects: Vec<RustProject>) -> Snapshot {
+        Snapshot { created: Utc::now(), projects }
+    }
+
+    #[test]
+    fn test_diff_reports_growth_new_and_removed() {
+        let before = snapshot(vec![RustProject::fixture("/a", 100), RustProject::fixture("/b", 500), RustProject::fixture("/c", 10)]);
+        let after = snapshot(vec![RustProject::fixture("/a", 900), RustProject::fixture("/b", 500), RustProject::fixture("/d", 50)]);
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                SizeDelta::Changed { path: "/a".into(), before: 100, after: 900 },
+                SizeDelta::New { path: "/d".into(), size: 50 },
+                SizeDelta::Removed { path: "/c".into(), size: 10 },
+            ]
+        );
+    }
+}
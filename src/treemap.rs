@@ -0,0 +1,175 @@
+use std::{collections::BTreeMap, fs, path::Path};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use crate::{cli::PathStyle, project::{ArtifactType, RustProject}, utils};
+
+/// a node of the size hierarchy written by `--treemap`, shaped like d3's hierarchy
+/// input: inner nodes have children, leaves have a value in bytes
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TreemapNode {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreemapNode>,
+}
+
+impl TreemapNode {
+    fn leaf(name: String, value: u64) -> Self {
+        Self { name, value: Some(value), children: Vec::new() }
+    }
+
+    fn inner(name: String, children: Vec<TreemapNode>) -> Self {
+        Self { name, value: None, children }
+    }
+
+    /// search directory → project → artifact type → directory below the target
+    pub fn from_projects(root: &Path, projects: &[RustProject], paths: &PathStyle) -> Self {
+        let children = projects
+            .iter()
+            .filter(|p| p.total_cleanable_size() > 0)
+            .map(|project| Self::project_node(project, paths))
+            .collect();
+        Self::inner(utils::styled_path(root, paths).display().to_string(), children)
+    }
+
+    /// artifacts nest (`debug` holds `debug/deps`), so each leaf only counts what isn't
+    /// in a nested artifact, and whatever no artifact covers goes to `other`; artifacts
+    /// outside the target (config patterns, `vendor/`) make up the one `extra artifacts` leaf
+    fn project_node(project: &RustProject, paths: &PathStyle) -> Self {
+        let target_artifacts: Vec<_> = match &project.target_dir {
+            Some(target) => project.build_artifacts.iter().filter(|a| a.path.starts_with(target)).collect(),
+            None => Vec::new(),
+        };
+
+        let mut by_type: BTreeMap<&ArtifactType, Vec<TreemapNode>> = BTreeMap::new();
+        let mut covered = 0u64;
+        for artifact in &target_artifacts {
+            let nested: u64 = target_artifacts
+                .iter()
+                .filter(|other| other.path != artifact.path && other.path.starts_with(&artifact.path))
+                .map(|other| other.size)
+                .sum();
+            let own = artifact.size.saturating_sub(nested);
+            covered += own;
+
+            let relative = project
+                .target_dir
+                .as_ref()
+                .and_then(|target| artifact.path.strip_prefix(target).ok())
+                .unwrap_or(&artifact.path);
+            by_type
+                .entry(&artifact.artifact_type)
+                .or_default()
+                .push(Self::leaf(relative.display().to_string(), own));
+        }
+
+        let mut children: Vec<_> = by_type
+            .into_iter()
+            .map(|(artifact_type, leaves)| Self::inner(format!("{:?}", artifact_type), leaves))
+            .collect();
+        let other = project.target_size.saturating_sub(covered);
+        if other > 0 {
+            children.push(Self::leaf("other".to_string(), other));
+        }
+        if project.extra_artifacts_size > 0 {
+            children.push(Self::leaf("extra artifacts".to_string(), project.extra_artifacts_size));
+        }
+
+        Self::inner(utils::styled_path(&project.path, paths).display().to_string(), children)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.value.unwrap_or(0) + self.children.iter().map(TreemapNode::total).sum::<u64>()
+    }
+
+    /// one `a;b;c bytes` line per leaf, the folded stack format `inferno` reads
+    pub fn folded(&self) -> String {
+        let mut lines = Vec::new();
+        self.fold_into(&mut Vec::new(), &mut lines);
+        lines.join("\n") + "\n"
+    }
+
+    fn fold_into<'a>(&'a self, stack: &mut Vec<&'a str>, lines: &mut Vec<String>) {
+        stack.push(&self.name);
+        if let Some(value) = self.value {
+            let frames: Vec<_> = stack.iter().map(|frame| frame.replace(';', "_")).collect();
+            lines.push(format!("{} {}", frames.join(";"), value));
+        }
+        for child in &self.children {
+            child.fold_into(stack, lines);
+        }
+        stack.pop();
+    }
+
+    /// folded stacks for a `.folded` file, nested JSON otherwise
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = if path.extension().is_some_and(|ext| ext == "folded") {
+            self.folded()
+        } else {
+            serde_json::to_string(self)?
+        };
+        fs::write(path, content).with_context(|| format!("Failed to write treemap {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::BuildArtifact;
+
+    fn project() -> RustProject {
+        let artifact = |path: &str, artifact_type, size| BuildArtifact {
+            path: path.into(),
+            artifact_type,
+            size,
+            last_modified: None,
+            label: None,
+        };
+        RustProject {
+            target_dir: Some("/src/app/target".into()),
+            build_artifacts: vec![
+                artifact("/src/app/target/debug", ArtifactType::Target, 300),
+                artifact("/src/app/target/debug/deps", ArtifactType::Dependencies, 200),
+            ],
+            ..RustProject::fixture("/src/app", 350)
+        }
+    }
+
+    #[test]
+    fn test_artifacts_outside_the_target_are_only_extras() {
+        let mut project = project();
+        project.build_artifacts.push(BuildArtifact {
+            path: "/src/app/vendor".into(),
+            artifact_type: ArtifactType::VendoredDeps,
+            size: 70,
+            last_modified: None,
+            label: None,
+        });
+        project.extra_artifacts = vec!["/src/app/vendor".into()];
+        project.extra_artifacts_size = 70;
+        let tree = TreemapNode::from_projects(Path::new("/src"), &[project], &PathStyle::Absolute);
+
+        assert_eq!(tree.total(), 420);
+        assert_eq!(
+            tree.folded(),
+            "/src;/src/app;Target;debug 100\n\
+             /src;/src/app;Dependencies;debug/deps 200\n\
+             /src;/src/app;other 50\n\
+             /src;/src/app;extra artifacts 70\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_artifacts_are_counted_once() {
+        let tree = TreemapNode::from_projects(Path::new("/src"), &[project()], &PathStyle::Absolute);
+
+        assert_eq!(tree.total(), 350);
+        assert_eq!(
+            tree.folded(),
+            "/src;/src/app;Target;debug 100\n\
+             /src;/src/app;Dependencies;debug/deps 200\n\
+             /src;/src/app;other 50\n"
+        );
+    }
+}
@@ -1,16 +1,21 @@
 use anyhow::Result;
-use std::{process, io::stdout};
+use std::{collections::{BTreeMap, BTreeSet, HashSet}, panic, process, sync::Arc, io::{stdin, stdout, IsTerminal}, path::{Path, PathBuf}, time::{Duration, Instant}};
+use clap::ValueEnum;
 use crate::{
     utils,
-    cli::Cli,
-    project::RustProject,
-    scanner::ProjectScanner,
+    error::{self, RskillError},
+    ExitCode,
+    cli::{Cli, FooterMetric, SortBy, TimestampFormat},
+    project::{ArtifactType, BuildFreshness, ProjectStatus, RustProject, DEFAULT_STALE_DAYS},
+    report::{DeleteReport, ReportError},
+    scanner::{ProjectScanner, ScanStats},
 };
+use tokio::{sync::mpsc::UnboundedReceiver, task::JoinHandle};
 use crossterm::{
     cursor,
     execute,
     terminal,
-    event::{self, Event, KeyCode, KeyEvent},
+    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
 };
 use ratatui::{
     Frame,
@@ -18,16 +23,117 @@ use ratatui::{
     backend::Backend,
     backend::CrosstermBackend,
     style::{Color as RatauiColor, Modifier, Style},
+    text::{Span, Spans},
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
 };
 
+/// days added or removed from the stale cutoff per `[` / `]` press
+const STALE_DAYS_STEP: i64 = 7;
+
+/// projects above this size are highlighted as moderate (1 GB)
+const MODERATE_SIZE_THRESHOLD: u64 = 1024 * 1024 * 1024;
+/// projects above this size are highlighted as large (5 GB)
+const LARGE_SIZE_THRESHOLD: u64 = 5 * 1024 * 1024 * 1024;
+
+/// below this the layout (header, list, footer and margins) can't fit, a notice is shown instead
+const MIN_TERMINAL_WIDTH: u16 = 40;
+const MIN_TERMINAL_HEIGHT: u16 = 14;
+
+/// every key binding with its description, shown in the `?` help overlay
+const KEY_BINDINGS: &[(&str, &str)] = &[
+    ("↑/k, ↓/j", "move the selection"),
+    ("g/home, G/end", "jump to the first / last project"),
+    ("space, del, D", "delete the selected project's artifacts"),
+    ("a", "delete the artifacts of all projects, esc stops"),
+    ("p", "protect / unprotect the selected project for this session"),
+    ("c", "mark the project for comparison, the second mark compares"),
+    ("enter", "toggle the details pane"),
+    ("t", "change the artifact order in the details pane"),
+    ("i", "invert the sort order"),
+    ("b", "cycle the secondary sort key"),
+    ("[ / ]", "lower / raise the stale cutoff"),
+    ("o", "open the project directory"),
+    ("y", "copy the project path to the clipboard"),
+    ("r", "rescan everything"),
+    ("R", "rescan the selected project"),
+    ("m", "open the menu of sort orders and actions"),
+    ("?", "toggle this help"),
+    ("q, esc", "quit"),
+    ("ctrl-c", "abort"),
+];
+
+/// what choosing a menu entry does, most entries stand in for a key binding
+#[derive(Clone, Copy)]
+enum MenuAction {
+    Sort(SortBy),
+    Key(KeyCode),
+}
+
+/// entries of the `m` menu, in the order they're listed
+const MENU_ITEMS: &[(&str, MenuAction)] = &[
+    ("Sort by size", MenuAction::Sort(SortBy::Size)),
+    ("Sort by path", MenuAction::Sort(SortBy::Path)),
+    ("Sort by last modified", MenuAction::Sort(SortBy::LastMod)),
+    ("Invert the sort order", MenuAction::Key(KeyCode::Char('i'))),
+    ("Cycle the secondary sort key", MenuAction::Key(KeyCode::Char('b'))),
+    ("Lower the stale cutoff", MenuAction::Key(KeyCode::Char('['))),
+    ("Raise the stale cutoff", MenuAction::Key(KeyCode::Char(']'))),
+    ("Toggle the details pane", MenuAction::Key(KeyCode::Enter)),
+    ("Protect / unprotect the project", MenuAction::Key(KeyCode::Char('p'))),
+    ("Mark the project for comparison", MenuAction::Key(KeyCode::Char('c'))),
+    ("Delete the project's artifacts", MenuAction::Key(KeyCode::Char('D'))),
+    ("Delete the artifacts of all projects", MenuAction::Key(KeyCode::Char('a'))),
+    ("Open the project directory", MenuAction::Key(KeyCode::Char('o'))),
+    ("Copy the project path", MenuAction::Key(KeyCode::Char('y'))),
+    ("Rescan the project", MenuAction::Key(KeyCode::Char('R'))),
+    ("Rescan everything", MenuAction::Key(KeyCode::Char('r'))),
+    ("Show the key bindings", MenuAction::Key(KeyCode::Char('?'))),
+    ("Quit", MenuAction::Key(KeyCode::Char('q'))),
+];
+
 pub struct InteractiveUI {
     cli: Cli,
     projects: Vec<RustProject>,
+    /// number of projects the scan found, before `--top` truncation
+    found_count: usize,
+    scan_stats: Option<ScanStats>,
+    active_scan: Option<ActiveScan>,
+    /// an `r` rescan ran, an empty result then keeps the session open
+    rescanned: bool,
     selected_index: usize,
+    /// first project row shown, moved along to keep the selection visible
+    list_offset: usize,
     total_deleted_size: u64,
     deleted_count: usize,
+    permission_denied_count: usize,
+    failed_deletion_count: usize,
+    /// what was deleted and what failed, for `--report-json`
+    report: DeleteReport,
+    /// project whose deletion hit permission denied, awaiting skip or sudo retry
+    pending_permission_retry: Option<PathBuf>,
+    /// project with uncommitted git changes, awaiting confirmation before deletion
+    pending_delete_confirmation: Option<PathBuf>,
+    /// projects with uncommitted git changes, awaiting a decision before a delete-all
+    pending_bulk_confirmation: Option<HashSet<PathBuf>>,
+    /// a delete-all in progress
+    bulk_deletion: Option<BulkDeletion>,
+    /// canonical paths of the projects protected with `p`, kept across rescans
+    session_protected: HashSet<PathBuf>,
+    status_message: Option<String>,
+    needs_full_redraw: bool,
+    show_details: bool,
+    show_help: bool,
+    /// projects marked with `c`, compared side by side once there are two
+    compare_marks: Vec<PathBuf>,
+    show_compare: bool,
+    /// highlighted entry of the open `m` menu, `None` while it's closed
+    menu_selection: Option<usize>,
+    /// cutoff for the Active/Stale status, adjustable live
+    stale_days: i64,
+    artifact_sort: ArtifactSort,
+    /// kept alive for the session, on X11 the copied text vanishes once it's dropped
+    clipboard: Option<arboard::Clipboard>,
 }
 
 impl InteractiveUI {
@@ -35,13 +141,72 @@ impl InteractiveUI {
         Self {
             cli,
             projects: Vec::new(),
+            found_count: 0,
+            scan_stats: None,
+            active_scan: None,
+            rescanned: false,
             selected_index: 0,
+            list_offset: 0,
             total_deleted_size: 0,
             deleted_count: 0,
+            permission_denied_count: 0,
+            failed_deletion_count: 0,
+            report: DeleteReport::default(),
+            pending_permission_retry: None,
+            pending_delete_confirmation: None,
+            pending_bulk_confirmation: None,
+            bulk_deletion: None,
+            session_protected: HashSet::new(),
+            status_message: None,
+            needs_full_redraw: false,
+            show_details: false,
+            show_help: false,
+            compare_marks: Vec::new(),
+            show_compare: false,
+            menu_selection: None,
+            stale_days: DEFAULT_STALE_DAYS,
+            artifact_sort: ArtifactSort::Size,
+            clipboard: None,
         }
     }
 
-    pub async fn run(&mut self) -> Result<()> {
+    pub async fn run(&mut self) -> Result<ExitCode> {
+        // make sure a panic mid-session doesn't leave the shell in raw mode, the previous
+        // hook is put back once the session is over
+        let previous_hook = Arc::new(panic::take_hook());
+        let hook = Arc::clone(&previous_hook);
+        panic::set_hook(Box::new(move |info| {
+            Self::restore_terminal();
+            hook(info);
+        }));
+
+        let started = Instant::now();
+        let result = self.run_session().await;
+
+        // dropping our hook releases its handle on the previous one
+        drop(panic::take_hook());
+        if let Ok(previous_hook) = Arc::try_unwrap(previous_hook) {
+            panic::set_hook(previous_hook);
+        }
+
+        if let Some(path) = &self.cli.report_json {
+            self.report.projects_scanned = self.found_count;
+            self.report.duration_ms = started.elapsed().as_millis() as u64;
+            self.report.dry_run = self.cli.dry_run;
+            self.report.save(path)?;
+        }
+
+        if self.cli.profile {
+            if let Some(stats) = &self.scan_stats {
+                stats.profile.print();
+            }
+        }
+
+        result
+    }
+
+    /// the TUI itself, between entering and leaving the alternate screen
+    async fn run_session(&mut self) -> Result<ExitCode> {
         terminal::enable_raw_mode()?;
         execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
 
@@ -52,84 +217,323 @@ impl InteractiveUI {
 
         execute!(terminal.backend_mut(), terminal::LeaveAlternateScreen, cursor::Show)?;
         terminal::disable_raw_mode()?;
-
         result
     }
 
-    async fn run_interactive_loop<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
-        let scanner = ProjectScanner::new(self.cli.clone());
-        self.projects = scanner.scan().await?;
-
-        if self.projects.is_empty() {
-            println!("No Rust projects found!");
-            return Ok(());
+    /// whether the TUI can run here: both ends are a terminal that isn't `dumb` and
+    /// raw mode can be switched on
+    pub fn terminal_available() -> bool {
+        if !stdin().is_terminal() || !stdout().is_terminal() {
+            return false;
+        }
+        if std::env::var_os("TERM").is_some_and(|term| term == "dumb") {
+            return false;
         }
+        terminal::enable_raw_mode().is_ok() && terminal::disable_raw_mode().is_ok()
+    }
+
+    fn restore_terminal() {
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(stdout(), terminal::LeaveAlternateScreen, cursor::Show);
+    }
+
+    async fn run_interactive_loop<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<ExitCode> {
+        self.start_scan();
 
         loop {
+            if self.poll_scan().await? && self.projects.is_empty() {
+                if !self.rescanned {
+                    println!("No Rust projects found!");
+                    return Ok(ExitCode::NothingFound);
+                }
+                self.status_message = Some("No Rust projects found | r: rescan".to_string());
+            }
+
+            if self.needs_full_redraw {
+                terminal.clear()?;
+                self.needs_full_redraw = false;
+            }
+
+            if self.bulk_deletion_due() {
+                self.continue_bulk_deletion();
+            }
+
             terminal.draw(|f| self.draw_ui(f))?;
 
-            if event::poll(std::time::Duration::from_millis(100))? {
+            // keep going right away while a delete-all has work to do
+            let timeout = if self.bulk_deletion_due() {
+                Duration::ZERO
+            } else {
+                Duration::from_millis(100)
+            };
+            if event::poll(timeout)? {
                 if let Event::Key(key_event) = event::read()? {
                     match self.handle_key_event(key_event).await? {
                         ControlFlow::Exit => break,
+                        ControlFlow::Abort => return Ok(ExitCode::Aborted),
                         ControlFlow::Continue => continue,
                     }
                 }
             }
         }
-        Ok(())
+
+        if self.permission_denied_count + self.failed_deletion_count > 0 {
+            Ok(ExitCode::DeleteFailed)
+        } else {
+            Ok(ExitCode::Success)
+        }
     }
 
-    fn draw_ui<B: Backend>(&self, f: &mut Frame<B>) {
+    fn draw_ui<B: Backend>(&mut self, f: &mut Frame<B>) {
         let size = f.size();
+        if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+            let message = format!(
+                "Terminal too small ({}x{}), needs at least {}x{}",
+                size.width, size.height, MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT
+            );
+            f.render_widget(Paragraph::new(message).wrap(Wrap { trim: true }), size);
+            return;
+        }
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .margin(1)
             .constraints([
                 Constraint::Length(1),
                 Constraint::Min(5),
-                Constraint::Length(5),
+                Constraint::Length(6),
             ])
             .split(size);
 
         self.draw_header(f, chunks[0]);
-        self.draw_project_list(f, chunks[1]);
+        if self.show_details {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(chunks[1]);
+            self.draw_project_list(f, columns[0]);
+            self.draw_details(f, columns[1]);
+        } else {
+            self.draw_project_list(f, chunks[1]);
+        }
         self.draw_footer(f, chunks[2]);
+
+        if self.show_help {
+            Self::draw_help(f, size);
+        } else if self.show_compare {
+            self.draw_comparison(f, size);
+        } else if let Some(selected) = self.menu_selection {
+            Self::draw_menu(f, size, selected);
+        }
+    }
+
+    fn draw_menu<B: Backend>(f: &mut Frame<B>, size: Rect, selected: usize) {
+        let items: Vec<ListItem> = MENU_ITEMS
+            .iter()
+            .map(|(label, action)| {
+                let key = match action {
+                    MenuAction::Sort(_) => String::new(),
+                    MenuAction::Key(KeyCode::Char(c)) => c.to_string(),
+                    MenuAction::Key(KeyCode::Enter) => "enter".to_string(),
+                    MenuAction::Key(_) => String::new(),
+                };
+                ListItem::new(format!("{:<38} {:>5}", label, key))
+            })
+            .collect();
+
+        let width = 48u16.min(size.width);
+        let height = (MENU_ITEMS.len() as u16 + 2).min(size.height);
+        let area = Rect::new(
+            size.x + (size.width - width) / 2,
+            size.y + (size.height - height) / 2,
+            width,
+            height,
+        );
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Menu (enter: choose, esc: close)"))
+            .highlight_style(Style::default().fg(RatauiColor::Black).bg(RatauiColor::Cyan));
+        let mut state = ListState::default();
+        state.select(Some(selected));
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(list, area, &mut state);
+    }
+
+    /// move through the open menu; choosing an entry closes it and yields the key it
+    /// stands for, sort orders are applied right away
+    fn handle_menu_key(&mut self, selected: usize, code: KeyCode) -> Option<KeyEvent> {
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.menu_selection = Some(selected.checked_sub(1).unwrap_or(MENU_ITEMS.len() - 1));
+                None
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.menu_selection = Some((selected + 1) % MENU_ITEMS.len());
+                None
+            }
+            KeyCode::Enter => {
+                self.menu_selection = None;
+                match MENU_ITEMS[selected].1 {
+                    MenuAction::Sort(sort_by) => {
+                        self.cli.sort = sort_by;
+                        self.resort();
+                        None
+                    }
+                    MenuAction::Key(code) => Some(KeyEvent::new(code, KeyModifiers::NONE)),
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('q') => {
+                self.menu_selection = None;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// the artifact breakdowns of the two marked projects, the type differing most first
+    fn draw_comparison<B: Backend>(&self, f: &mut Frame<B>, size: Rect) {
+        let marked: Vec<_> = self
+            .compare_marks
+            .iter()
+            .filter_map(|path| self.projects.iter().find(|p| &p.path == path))
+            .collect();
+        let [a, b] = marked[..] else {
+            return;
+        };
+
+        let (sizes_a, sizes_b) = (a.artifact_sizes(), b.artifact_sizes());
+        let mut types: Vec<_> = sizes_a.keys().chain(sizes_b.keys()).collect::<BTreeSet<_>>().into_iter().collect();
+        let size_of = |sizes: &BTreeMap<ArtifactType, u64>, t: &ArtifactType| sizes.get(t).copied().unwrap_or(0);
+        types.sort_by_key(|t| std::cmp::Reverse(size_of(&sizes_a, t).abs_diff(size_of(&sizes_b, t))));
+
+        let row = |label: &str, size_a: u64, size_b: u64| {
+            let diff = utils::format_size(size_a.abs_diff(size_b), self.cli.gb);
+            let sign = match size_a.cmp(&size_b) {
+                std::cmp::Ordering::Less => "+",
+                std::cmp::Ordering::Greater => "-",
+                std::cmp::Ordering::Equal => " ",
+            };
+            format!(
+                "{:<24} {:>12} {:>12} {:>13}",
+                label,
+                utils::format_size(size_a, self.cli.gb),
+                utils::format_size(size_b, self.cli.gb),
+                format!("{}{}", sign, diff)
+            )
+        };
+
+        let mut lines = vec![
+            Spans::from(format!(
+                "{:<24} {:>12} {:>12} {:>13}",
+                "",
+                utils::truncate_string(&a.display_name(), 12),
+                utils::truncate_string(&b.display_name(), 12),
+                "difference"
+            )),
+        ];
+        for (i, artifact_type) in types.iter().enumerate() {
+            let line = row(&format!("{:?}", artifact_type), size_of(&sizes_a, artifact_type), size_of(&sizes_b, artifact_type));
+            let style = if i == 0 {
+                Style::default().fg(RatauiColor::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            lines.push(Spans::from(Span::styled(line, style)));
+        }
+        lines.push(Spans::from(row("Total", a.total_cleanable_size(), b.total_cleanable_size())));
+
+        let width = 66u16.min(size.width);
+        let height = (lines.len() as u16 + 2).min(size.height);
+        let area = Rect::new(
+            size.x + (size.width - width) / 2,
+            size.y + (size.height - height) / 2,
+            width,
+            height,
+        );
+
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Compare (any key to close)"));
+
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_help<B: Backend>(f: &mut Frame<B>, size: Rect) {
+        let lines: Vec<String> = KEY_BINDINGS
+            .iter()
+            .map(|(keys, description)| format!("{:<16} {}", keys, description))
+            .collect();
+
+        let width = (lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 4).min(size.width);
+        let height = (lines.len() as u16 + 2).min(size.height);
+        let area = Rect::new(
+            size.x + (size.width - width) / 2,
+            size.y + (size.height - height) / 2,
+            width,
+            height,
+        );
+
+        let paragraph = Paragraph::new(lines.join("\n"))
+            .block(Block::default().borders(Borders::ALL).title("Keys (any key to close)"));
+
+        f.render_widget(Clear, area);
+        f.render_widget(paragraph, area);
     }
 
     fn draw_header<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let header = Paragraph::new("RSKILL - Rust Project Cleaner")
+        let title = if self.active_scan.is_some() {
+            format!("RSKILL - Rust Project Cleaner (scanning... {} found)", self.projects.len())
+        } else {
+            "RSKILL - Rust Project Cleaner".to_string()
+        };
+
+        let header = Paragraph::new(title)
             .style(Style::default().fg(RatauiColor::Cyan).add_modifier(Modifier::BOLD))
             .alignment(Alignment::Center);
         f.render_widget(header, area);
     }
 
-    fn draw_project_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+    fn draw_project_list<B: Backend>(&mut self, f: &mut Frame<B>, area: Rect) {
+        // rows inside the border
+        let visible_rows = (area.height as usize).saturating_sub(2).max(1);
+        if self.selected_index < self.list_offset {
+            self.list_offset = self.selected_index;
+        } else if self.selected_index >= self.list_offset + visible_rows {
+            self.list_offset = self.selected_index + 1 - visible_rows;
+        }
+
         let items: Vec<ListItem> = self
             .projects
             .iter()
             .enumerate()
+            .skip(self.list_offset)
+            .take(visible_rows)
             .map(|(i, p)| {
-                let size_str = p.format_size(self.cli.gb);
-                let path_str = utils::get_relative_path(&p.path);
+                let size_str = p.size_label(self.cli.gb);
+                let path_str = utils::display_path(&p.path, &self.cli.paths);
                 let path_display = utils::truncate_string(&path_str, 35);
-                let last_mod = p
-                    .days_since_modified()
-                    .map(|days| {
-                        if days == 0 {
-                            "Today".to_string()
-                        } else if days == 1 {
-                            "1 day ago".to_string()
-                        } else {
-                            format!("{} days ago", days)
-                        }
-                    })
-                    .unwrap_or_else(|| "Unknown".to_string());
+                let last_mod = utils::format_timestamp(
+                    p.last_modified,
+                    self.cli.timestamps.as_ref().unwrap_or(&TimestampFormat::Relative),
+                );
+
+                let git_marker = if p.git_repo { "git" } else { "" };
 
                 let content = format!(
-                    "{:<25} {:<12} {:<35} {:<15}",
-                    p.name, size_str, path_display, last_mod
+                    "{:<25} {:<12} {:<35} {:<22} {:<4}",
+                    p.display_name(), size_str, path_display, last_mod, git_marker
                 );
+                let status = p.status(self.stale_days);
+                let status_color = match status {
+                    ProjectStatus::Active => RatauiColor::Green,
+                    ProjectStatus::Stale => RatauiColor::Yellow,
+                    ProjectStatus::EmptyTarget => RatauiColor::Blue,
+                    ProjectStatus::NoTarget => RatauiColor::DarkGray,
+                    ProjectStatus::Protected => RatauiColor::Magenta,
+                    ProjectStatus::Workspace => RatauiColor::Cyan,
+                };
+                let status = Span::styled(status.label(), Style::default().fg(status_color));
 
                 let style = if i == self.selected_index {
                     Style::default()
@@ -137,10 +541,20 @@ impl InteractiveUI {
                         .bg(RatauiColor::Cyan)
                         .add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default()
+                    Style::default().fg(Self::size_color(p.total_cleanable_size()))
                 };
 
-                ListItem::new(content).style(style)
+                let mut spans = vec![Span::raw(content), Span::raw(" "), status];
+                if self.is_protected(p) {
+                    spans.push(Span::raw(" 🔒"));
+                }
+                if self.open_in_editor(p) {
+                    spans.push(Span::raw(" [open]"));
+                }
+                if self.compare_marks.contains(&p.path) {
+                    spans.push(Span::raw(" [cmp]"));
+                }
+                ListItem::new(Spans::from(spans)).style(style)
             })
             .collect();
 
@@ -151,17 +565,120 @@ impl InteractiveUI {
         f.render_widget(list, area);
     }
 
-    fn draw_footer<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
-        let total_projects = self.projects.len();
-        let total_size: u64 = self.projects.iter().map(|p| p.total_cleanable_size()).sum();
-        let total_size_str = utils::format_size(total_size, self.cli.gb);
-        let deleted_size_str = utils::format_size(self.total_deleted_size, self.cli.gb);
-
-        let text = vec![
-            format!("{} projects | {} cleanable", total_projects, total_size_str),
-            format!("{} deleted ({})", self.deleted_count, deleted_size_str),
-            "↑↓/jk: navigate | space/del/D: delete | o: open | r: refresh | q: quit".to_string(),
+    fn draw_details<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let Some(p) = self.projects.get(self.selected_index) else {
+            return;
+        };
+
+        let mut lines = vec![
+            format!("Name: {}", p.display_name()),
+            format!("Version: {}", p.version.as_deref().unwrap_or("unknown")),
+            format!("Kind: {}", p.crate_kind.map_or("unknown", |kind| kind.label())),
+            format!("Path: {}", utils::escape_path(&p.path)),
+            format!(
+                "Target: {}",
+                p.target_dir
+                    .as_deref()
+                    .map(utils::escape_path)
+                    .unwrap_or_else(|| "none".to_string())
+            ),
+            format!("Size: {}", p.format_size(self.cli.gb)),
+            format!(
+                "Last modified: {}",
+                utils::format_timestamp(
+                    p.last_modified,
+                    self.cli.timestamps.as_ref().unwrap_or(&TimestampFormat::Relative),
+                )
+            ),
+            format!(
+                "Dependencies: {} ({} from workspace)",
+                p.dependencies_count, p.workspace_dependencies_count
+            ),
+            format!("Lock file: {}", if p.has_lock_file { "yes" } else { "no" }),
+            format!("Rebuild: {}", p.rebuild_cost().label()),
+            format!(
+                "Build: {}",
+                match p.build_freshness() {
+                    Some(BuildFreshness::Stale) => "stale build (sources changed since, cheap to clean)",
+                    Some(BuildFreshness::Fresh) => "fresh build (cleaning wastes a good build)",
+                    None => "unknown",
+                }
+            ),
+            format!(
+                "Target filesystem: {}",
+                if p.target_on_other_filesystem() { "separate from the project" } else { "same as the project" }
+            ),
+            format!("Git: {}", if p.git_repo { "tracked repository" } else { "not a git repository" }),
         ];
+        if let Some(largest) = &p.largest_file {
+            let relative = p
+                .target_dir
+                .as_ref()
+                .and_then(|t| largest.path.strip_prefix(t).ok())
+                .unwrap_or(&largest.path);
+            lines.push(format!(
+                "Largest file: {} ({})",
+                utils::escape_path(relative),
+                utils::format_size(largest.size, self.cli.gb)
+            ));
+        }
+
+        if !p.build_artifacts.is_empty() {
+            lines.push(String::new());
+            lines.push(format!("Artifacts (by {}, t: change):", self.artifact_sort.label()));
+            let mut artifacts: Vec<_> = p.build_artifacts.iter().collect();
+            match self.artifact_sort {
+                ArtifactSort::Size => artifacts.sort_by_key(|a| std::cmp::Reverse(a.size)),
+                ArtifactSort::Type => {
+                    artifacts.sort_by(|a, b| a.artifact_type.cmp(&b.artifact_type).then(b.size.cmp(&a.size)))
+                }
+                ArtifactSort::LastModified => artifacts.sort_by_key(|a| std::cmp::Reverse(a.last_modified)),
+            }
+            for artifact in artifacts {
+                let relative = p
+                    .target_dir
+                    .as_ref()
+                    .and_then(|t| artifact.path.strip_prefix(t).ok())
+                    .unwrap_or(&artifact.path);
+                let kind = artifact
+                    .label
+                    .clone()
+                    .unwrap_or_else(|| format!("{:?}", artifact.artifact_type));
+                lines.push(format!(
+                    "  {:<12} {:<24} {}",
+                    utils::format_size(artifact.size, self.cli.gb),
+                    relative.display(),
+                    kind
+                ));
+            }
+        }
+
+        let paragraph = Paragraph::new(lines.join("\n"))
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title("Details"));
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn size_color(size: u64) -> RatauiColor {
+        if size > LARGE_SIZE_THRESHOLD {
+            RatauiColor::Red
+        } else if size > MODERATE_SIZE_THRESHOLD {
+            RatauiColor::Yellow
+        } else {
+            RatauiColor::Green
+        }
+    }
+
+    fn draw_footer<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let metrics: Vec<String> = self
+            .cli
+            .footer_metrics()
+            .iter()
+            .filter_map(|&metric| self.footer_metric(metric))
+            .collect();
+
+        let text = [metrics.join(" | "), self.status_message.clone().unwrap_or_default()];
 
         let paragraph = Paragraph::new(text.join("\n"))
             .wrap(Wrap { trim: true })
@@ -170,13 +687,145 @@ impl InteractiveUI {
         f.render_widget(paragraph, area);
     }
 
+    /// text of one footer metric, `None` while it has nothing to show
+    fn footer_metric(&self, metric: FooterMetric) -> Option<String> {
+        match metric {
+            FooterMetric::Projects if self.projects.len() < self.found_count => Some(format!(
+                "showing top {} of {} projects",
+                self.projects.len(),
+                self.found_count
+            )),
+            FooterMetric::Projects => Some(format!("{} projects", self.projects.len())),
+            FooterMetric::Cleanable if self.cli.no_sizes => Some("unknown cleanable".to_string()),
+            FooterMetric::Cleanable => {
+                let total_size: u64 = self.projects.iter().map(|p| p.total_cleanable_size()).sum::<u64>()
+                    + ProjectScanner::cargo_cache_size(&self.projects);
+                Some(format!("{} cleanable", utils::format_size(total_size, self.cli.gb)))
+            }
+            FooterMetric::ScanTime => self.scan_stats.as_ref().map(|stats| {
+                if stats.timed_out {
+                    format!("scan timed out after {:.1}s, results incomplete", stats.elapsed.as_secs_f64())
+                } else {
+                    format!("scanned in {:.1}s", stats.elapsed.as_secs_f64())
+                }
+            }),
+            FooterMetric::Stale => {
+                let stale_count = self.projects.iter().filter(|p| !p.is_likely_active(self.stale_days)).count();
+                Some(format!("{} stale (over {} days)", stale_count, self.stale_days))
+            }
+            FooterMetric::Sort => {
+                let name = |sort_by: &SortBy| sort_by.to_possible_value().map(|v| v.get_name().to_string());
+                let mut sort = format!("sorted by {}", name(&self.cli.sort).unwrap_or_default());
+                if let Some(then_by) = self.cli.then_by.as_ref().and_then(name) {
+                    sort.push_str(&format!(", then {}", then_by));
+                }
+                if self.cli.reverse {
+                    sort.push_str(" (reversed)");
+                }
+                Some(sort)
+            }
+            FooterMetric::Deleted => {
+                let mut deleted = format!(
+                    "{} deleted ({})",
+                    self.deleted_count,
+                    utils::format_size(self.total_deleted_size, self.cli.gb)
+                );
+                if self.permission_denied_count > 0 {
+                    deleted.push_str(&format!(", {} skipped: permission denied", self.permission_denied_count));
+                }
+                if self.failed_deletion_count > 0 {
+                    deleted.push_str(&format!(", {} failed", self.failed_deletion_count));
+                }
+                Some(deleted)
+            }
+            FooterMetric::Keys => {
+                Some("↑↓/jk: navigate | space: delete | enter: details | m: menu | ?: help | q: quit".to_string())
+            }
+        }
+    }
+
     async fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<ControlFlow> {
+        // ctrl-c aborts from anywhere, whatever prompt, menu or overlay is showing
+        if key_event.code == KeyCode::Char('c') && key_event.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(ControlFlow::Abort);
+        }
+
+        // the prompts hold a path rather than an index, the list may have been re-sorted since
+        if let Some(path) = self.pending_permission_retry.take() {
+            if let Some(index) = self.project_index(&path) {
+                self.handle_permission_retry(index, key_event.code)?;
+            }
+            return Ok(ControlFlow::Continue);
+        }
+
+        if let Some(dirty) = self.pending_bulk_confirmation.take() {
+            self.status_message = None;
+            match key_event.code {
+                KeyCode::Char('y') => self.bulk_deletion = Some(BulkDeletion::new(HashSet::new())),
+                KeyCode::Char('n') => self.bulk_deletion = Some(BulkDeletion::new(dirty)),
+                _ => {}
+            }
+            return Ok(ControlFlow::Continue);
+        }
+
+        if let Some(path) = self.pending_delete_confirmation.take() {
+            self.status_message = None;
+            if key_event.code == KeyCode::Char('y') {
+                if let Some(index) = self.project_index(&path) {
+                    self.delete_project(index).await?;
+                }
+            }
+            return Ok(ControlFlow::Continue);
+        }
+
+        if self.show_help {
+            self.show_help = false;
+            return Ok(ControlFlow::Continue);
+        }
+
+        if self.show_compare {
+            self.show_compare = false;
+            self.compare_marks.clear();
+            return Ok(ControlFlow::Continue);
+        }
+
+        // a chosen menu entry is handled like its key
+        let key_event = match self.menu_selection {
+            Some(selected) => match self.handle_menu_key(selected, key_event.code) {
+                Some(key_event) => key_event,
+                None => return Ok(ControlFlow::Continue),
+            },
+            None => key_event,
+        };
+
+        self.status_message = None;
+
         match key_event.code {
+            KeyCode::Char('?') => {
+                self.show_help = true;
+                Ok(ControlFlow::Continue)
+            }
+
+            KeyCode::Char('m') => {
+                self.menu_selection = Some(0);
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Esc if self.bulk_deletion.is_some() => {
+                if let Some(bulk) = self.bulk_deletion.take() {
+                    self.finish_bulk_deletion(bulk);
+                }
+                self.status_message.get_or_insert_with(|| "Stopped deleting all projects".to_string());
+                Ok(ControlFlow::Continue)
+            }
+
             KeyCode::Char('q') | KeyCode::Esc => Ok(ControlFlow::Exit),
             
             KeyCode::Up | KeyCode::Char('k') => {
                 if self.selected_index > 0 {
                     self.selected_index -= 1;
+                } else if self.cli.wrap {
+                    self.selected_index = self.projects.len().saturating_sub(1);
                 }
                 Ok(ControlFlow::Continue)
             }
@@ -184,90 +833,474 @@ impl InteractiveUI {
             KeyCode::Down | KeyCode::Char('j') => {
                 if self.selected_index < self.projects.len().saturating_sub(1) {
                     self.selected_index += 1;
+                } else if self.cli.wrap {
+                    self.selected_index = 0;
                 }
                 Ok(ControlFlow::Continue)
             }
+
+            KeyCode::Home | KeyCode::Char('g') => {
+                self.selected_index = 0;
+                Ok(ControlFlow::Continue)
+            }
+
+            KeyCode::End | KeyCode::Char('G') => {
+                self.selected_index = self.projects.len().saturating_sub(1);
+                Ok(ControlFlow::Continue)
+            }
             
             KeyCode::Delete | KeyCode::Char(' ') | KeyCode::Char('D') => {
                 self.delete_selected_project().await?;
                 Ok(ControlFlow::Continue)
             }
             
+            KeyCode::Enter => {
+                self.show_details = !self.show_details;
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Char('t') if self.show_details => {
+                self.artifact_sort = self.artifact_sort.next();
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Char('i') => {
+                self.cli.reverse = !self.cli.reverse;
+                self.resort();
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Char('b') => {
+                self.cli.then_by = match self.cli.then_by {
+                    None => Some(SortBy::Path),
+                    Some(SortBy::Path) => Some(SortBy::LastMod),
+                    Some(SortBy::LastMod) => Some(SortBy::Size),
+                    Some(SortBy::Size) => None,
+                };
+                self.resort();
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Char('[') => {
+                self.stale_days = (self.stale_days - STALE_DAYS_STEP).max(1);
+                Ok(ControlFlow::Continue)
+            }
+            
+            KeyCode::Char(']') => {
+                self.stale_days += STALE_DAYS_STEP;
+                Ok(ControlFlow::Continue)
+            }
+            
             KeyCode::Char('o') => {
                 self.open_selected_project()?;
                 Ok(ControlFlow::Continue)
             }
             
+            KeyCode::Char('y') => {
+                self.copy_selected_path();
+                Ok(ControlFlow::Continue)
+            }
+            
             KeyCode::Char('r') => {
                 self.refresh_projects().await?;
                 Ok(ControlFlow::Continue)
             }
             
+            KeyCode::Char('R') => {
+                self.refresh_selected_project().await?;
+                Ok(ControlFlow::Continue)
+            }
+            
             KeyCode::Char('a') => {
                 self.delete_all_projects().await?;
                 Ok(ControlFlow::Continue)
             }
+
+            KeyCode::Char('p') => {
+                self.toggle_session_protection();
+                Ok(ControlFlow::Continue)
+            }
+
+            KeyCode::Char('c') => {
+                self.toggle_compare_mark();
+                Ok(ControlFlow::Continue)
+            }
             
             _ => Ok(ControlFlow::Continue),
         }
     }
 
     async fn delete_selected_project(&mut self) -> Result<()> {
-        if let Some(project) = self.projects.get(self.selected_index) {
-            if let Some(target_dir) = &project.target_dir {
-                let size_before = project.total_cleanable_size();
-                
-                // confirm deletion for large or active projects
-                if !self.cli.delete_all && (project.is_likely_active() || size_before > 1024 * 1024 * 500) {
-                    // for now, skip confirmation in interactive mode
-                    // in a real implementation, you'd show a confirmation dialog
+        let Some(project) = self.projects.get(self.selected_index) else {
+            return Ok(());
+        };
+
+        if project.protected {
+            self.status_message = Some(format!("{} is protected by .rskillignore", project.name));
+            return Ok(());
+        }
+        if self.is_protected(project) {
+            self.status_message = Some(format!("{} is protected for this session, p: unprotect", project.name));
+            return Ok(());
+        }
+
+        if project.cleanable_dirs(self.cli.clean_scope()).is_empty() {
+            if project.shared_target {
+                self.status_message = Some(format!("{}: its target is shared with other projects, skipped", project.name));
+            }
+            return Ok(());
+        }
+
+        if project.target_dir.as_deref().is_some_and(utils::build_in_progress) {
+            self.status_message = Some(format!("{} is being built right now, skipped", project.name));
+            return Ok(());
+        }
+
+        if self.open_in_editor(project) {
+            self.status_message = Some(format!("{} looks open in an editor, skipped", project.name));
+            return Ok(());
+        }
+
+        // a dirty working tree hints at a project still being worked on, ask first
+        if !self.cli.delete_all && project.has_uncommitted_changes() {
+            self.pending_delete_confirmation = Some(project.path.clone());
+            self.status_message = Some(format!(
+                "{} has uncommitted git changes, delete its target anyway? y: delete, any other key: cancel",
+                project.name
+            ));
+            return Ok(());
+        }
+
+        self.delete_project(self.selected_index).await
+    }
+
+    async fn delete_project(&mut self, index: usize) -> Result<()> {
+        let Some(project) = self.projects.get(index) else {
+            return Ok(());
+        };
+        let size_before = project.cleanable_size(self.cli.clean_scope());
+        let project_dir = project.path.clone();
+        let vendored = project.cleanable_dirs(self.cli.clean_scope()).iter().any(|d| project.is_vendored_deps(d));
+        if !self.pre_delete_allows(&project_dir, size_before) {
+            return Ok(());
+        }
+
+        for dir in self.projects[index].cleanable_dirs(self.cli.clean_scope()).iter().filter(|d| d.exists()) {
+            match utils::remove_directory(dir, self.cli.dry_run) {
+                Ok(()) => {}
+                Err(RskillError::PermissionDenied { .. }) => {
+                    self.pending_permission_retry = Some(project_dir.clone());
+                    self.status_message = Some(if cfg!(unix) {
+                        format!("Permission denied: {} | s: retry with sudo, any other key: skip", dir.display())
+                    } else {
+                        format!("Permission denied: {} | press any key to skip", dir.display())
+                    });
+                    return Ok(());
                 }
-                
-                utils::remove_directory(target_dir, self.cli.dry_run)?;
-                
-                if !self.cli.dry_run {
-                    self.total_deleted_size += size_before;
-                    self.deleted_count += 1;
-                    
-                    // Update the project in our list
-                    if let Some(project_mut) = self.projects.get_mut(self.selected_index) {
-                        project_mut.target_dir = None;
-                        project_mut.target_size = 0;
-                        project_mut.build_artifacts.clear();
-                    }
+                Err(err) => {
+                    self.failed_deletion_count += 1;
+                    self.record_error(dir, &err);
+                    self.status_message = Some(format!("Error: {}", err));
+                    return Ok(());
                 }
             }
         }
+
+        self.post_delete(&project_dir, size_before);
+
+        if self.projects[index].target_symlink && !self.cli.exclude_symlinked_targets {
+            self.status_message = Some(format!(
+                "{}: target is a symlink, only the link was removed",
+                self.projects[index].name
+            ));
+        }
+        if vendored {
+            self.status_message = Some(format!(
+                "{}: vendored dependencies deleted, run `cargo vendor` before building again",
+                self.projects[index].name
+            ));
+        }
+
+        if !self.cli.dry_run {
+            self.mark_deleted(index, size_before);
+            if self.cli.rebuild_after {
+                self.rebuild_project(index).await?;
+            }
+        }
         Ok(())
     }
 
+    /// warm the cleaned project up again, the build output is shown on the plain terminal
+    async fn rebuild_project(&mut self, index: usize) -> Result<()> {
+        let project_dir = self.projects[index].path.clone();
+        let command = self.cli.rebuild_command().to_string();
+
+        Self::restore_terminal();
+        println!("Running `{}` in {}...", command, project_dir.display());
+        let started = std::time::Instant::now();
+        let result = utils::run_command_in(&project_dir, &command);
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        self.needs_full_redraw = true;
+
+        self.status_message = Some(match result {
+            Ok(true) => format!("Rebuilt {} in {:.1}s", project_dir.display(), started.elapsed().as_secs_f64()),
+            Ok(false) => format!("`{}` failed in {}", command, project_dir.display()),
+            Err(err) => format!("Could not run `{}`: {}", command, err),
+        });
+
+        // the rebuilt target counts as cleanable again
+        if let Ok(project) = ProjectScanner::new(self.cli.clone()).rescan_project(&project_dir).await {
+            self.projects[index] = project;
+        }
+        Ok(())
+    }
+
+    /// start deleting every project, one project per loop iteration so the list keeps
+    /// updating and `--throttle` pauses don't freeze the screen
     async fn delete_all_projects(&mut self) -> Result<()> {
-        let mut total_deleted = 0u64;
-        let mut count_deleted = 0;
-        
-        for project in &mut self.projects {
-            if let Some(target_dir) = &project.target_dir {
-                let size_before = project.target_size;
-                
-                utils::remove_directory(target_dir, self.cli.dry_run)?;
-                
-                if !self.cli.dry_run {
-                    total_deleted += size_before;
-                    count_deleted += 1;
-                    
-                    project.target_dir = None;
-                    project.target_size = 0;
-                    project.build_artifacts.clear();
+        if self.bulk_deletion.is_some() {
+            return Ok(());
+        }
+
+        // like a single delete, projects with a dirty working tree are asked about first
+        let dirty: HashSet<PathBuf> = if self.cli.delete_all {
+            HashSet::new()
+        } else {
+            self.projects
+                .iter()
+                .filter(|p| !p.cleanable_dirs(self.cli.clean_scope()).is_empty() && p.has_uncommitted_changes())
+                .map(|p| p.path.clone())
+                .collect()
+        };
+        if dirty.is_empty() {
+            self.bulk_deletion = Some(BulkDeletion::new(HashSet::new()));
+        } else {
+            self.status_message = Some(format!(
+                "{} projects have uncommitted git changes | y: delete them too, n: skip them, any other key: cancel",
+                dirty.len()
+            ));
+            self.pending_bulk_confirmation = Some(dirty);
+        }
+        Ok(())
+    }
+
+    fn bulk_deletion_due(&self) -> bool {
+        self.bulk_deletion.as_ref().is_some_and(|bulk| Instant::now() >= bulk.resume_at)
+    }
+
+    /// delete the next project of a running delete-all, skipping the ones that can't go
+    fn continue_bulk_deletion(&mut self) {
+        let Some(mut bulk) = self.bulk_deletion.take() else {
+            return;
+        };
+
+        while bulk.next < self.projects.len() {
+            let index = bulk.next;
+            bulk.next += 1;
+
+            let project = &self.projects[index];
+            if self.is_protected(project) {
+                continue;
+            }
+            if bulk.skip.contains(&project.path) {
+                bulk.dirty += 1;
+                continue;
+            }
+
+            let dirs = project.cleanable_dirs(self.cli.clean_scope());
+            if dirs.is_empty() {
+                continue;
+            }
+            if project.target_dir.as_deref().is_some_and(utils::build_in_progress) {
+                bulk.building += 1;
+                continue;
+            }
+            if self.open_in_editor(project) {
+                bulk.open += 1;
+                continue;
+            }
+            let size_before = project.cleanable_size(self.cli.clean_scope());
+            let project_dir = project.path.clone();
+            let vendored = dirs.iter().any(|d| project.is_vendored_deps(d));
+            if !self.pre_delete_allows(&project_dir, size_before) {
+                continue;
+            }
+
+            let mut result = Ok(());
+            for dir in dirs.iter().filter(|d| d.exists()) {
+                result = utils::remove_directory(dir, self.cli.dry_run);
+                if let Err(err) = &result {
+                    self.record_error(dir, err);
+                    break;
+                }
+            }
+
+            match result {
+                Ok(()) => {
+                    self.post_delete(&project_dir, size_before);
+                    if vendored {
+                        bulk.vendored += 1;
+                    }
+                    if !self.cli.dry_run {
+                        self.mark_deleted(index, size_before);
+                    }
                 }
+                Err(RskillError::PermissionDenied { .. }) => bulk.denied += 1,
+                Err(_) => bulk.failed += 1,
             }
+
+            self.status_message = Some(format!(
+                "Deleting all: {}/{} projects checked (esc: stop)",
+                bulk.next,
+                self.projects.len()
+            ));
+            bulk.resume_at = Instant::now() + self.cli.throttle.unwrap_or_default();
+            self.bulk_deletion = Some(bulk);
+            return;
         }
-        
-        self.total_deleted_size += total_deleted;
-        self.deleted_count += count_deleted;
-        
+
+        self.finish_bulk_deletion(bulk);
+    }
+
+    fn finish_bulk_deletion(&mut self, bulk: BulkDeletion) {
+        self.permission_denied_count += bulk.denied;
+        self.failed_deletion_count += bulk.failed;
+
+        self.status_message = if bulk.denied + bulk.failed + bulk.building + bulk.open + bulk.dirty > 0 {
+            Some(format!(
+                "{} projects skipped: permission denied, {} failed, {} being built, {} open in an editor, {} uncommitted changes",
+                bulk.denied, bulk.failed, bulk.building, bulk.open, bulk.dirty
+            ))
+        } else {
+            None
+        };
+        if bulk.vendored > 0 {
+            let notice = format!(
+                "{} projects lost their vendored dependencies, run `cargo vendor` before building them again",
+                bulk.vendored
+            );
+            self.status_message = Some(match self.status_message.take() {
+                Some(skipped) => format!("{} | {}", skipped, notice),
+                None => notice,
+            });
+        }
+    }
+
+    /// whether `--pre-delete-cmd` lets a project be cleaned, a refusal shows in the status line
+    fn pre_delete_allows(&mut self, project_dir: &Path, size: u64) -> bool {
+        if self.cli.pre_delete_cmd.is_none() {
+            return true;
+        }
+
+        // the hook writes to the terminal underneath the TUI
+        self.needs_full_redraw = true;
+        match utils::run_delete_hook(self.cli.pre_delete_cmd.as_deref(), self.cli.dry_run, project_dir, size) {
+            Ok(true) => true,
+            Ok(false) => {
+                self.status_message = Some(format!(
+                    "Skipped {}: declined by the pre-delete command",
+                    utils::escape_path(project_dir)
+                ));
+                false
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Skipped {}: pre-delete command: {}", utils::escape_path(project_dir), err));
+                false
+            }
+        }
+    }
+
+    /// run `--post-delete-cmd`, the deletion already happened so a failure is only reported
+    fn post_delete(&mut self, project_dir: &Path, size: u64) {
+        if self.cli.post_delete_cmd.is_none() {
+            return;
+        }
+
+        self.needs_full_redraw = true;
+        match utils::run_delete_hook(self.cli.post_delete_cmd.as_deref(), self.cli.dry_run, project_dir, size) {
+            Ok(true) => {}
+            Ok(false) => {
+                self.status_message = Some(format!("post-delete command failed for {}", utils::escape_path(project_dir)));
+            }
+            Err(err) => self.status_message = Some(format!("post-delete command: {}", err)),
+        }
+    }
+
+    fn handle_permission_retry(&mut self, index: usize, key: KeyCode) -> Result<()> {
+        let Some(project) = self.projects.get(index) else {
+            return Ok(());
+        };
+        let dirs: Vec<_> = project.cleanable_dirs(self.cli.clean_scope()).into_iter().filter(|d| d.exists()).collect();
+        let Some(first_dir) = dirs.first().cloned() else {
+            return Ok(());
+        };
+
+        #[cfg(unix)]
+        if key == KeyCode::Char('s') {
+            let size_before = project.cleanable_size(self.cli.clean_scope());
+
+            // hand the terminal back so sudo can prompt for a password
+            Self::restore_terminal();
+            let mut result = Ok(());
+            for dir in &dirs {
+                println!("Removing {} with sudo...", dir.display());
+                result = utils::remove_directory_with_sudo(dir);
+                if result.is_err() {
+                    break;
+                }
+            }
+            terminal::enable_raw_mode()?;
+            execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+            self.needs_full_redraw = true;
+
+            match result {
+                Ok(()) => {
+                    let project_dir = self.projects[index].path.clone();
+                    self.post_delete(&project_dir, size_before);
+                    self.mark_deleted(index, size_before);
+                    self.status_message = Some(format!("Deleted {} with sudo", first_dir.display()));
+                }
+                Err(err) => {
+                    self.permission_denied_count += 1;
+                    self.record_error(&first_dir, &err);
+                    self.status_message = Some(format!("sudo failed, skipped: {}", err));
+                }
+            }
+            return Ok(());
+        }
+
+        #[cfg(not(unix))]
+        let _ = key;
+
+        self.permission_denied_count += 1;
+        self.record_error(&first_dir, "permission denied");
+        self.status_message = Some(format!("Skipped {}: permission denied", first_dir.display()));
         Ok(())
     }
 
+    fn record_error(&mut self, path: &Path, message: impl ToString) {
+        self.report.errors.push(ReportError { path: path.to_path_buf(), message: message.to_string() });
+    }
+
+    fn mark_deleted(&mut self, index: usize, size_before: u64) {
+        self.total_deleted_size += size_before;
+        self.deleted_count += 1;
+        if let Some(project) = self.projects.get(index) {
+            self.report.record_deleted(&project.path, size_before);
+        }
+        
+        // Update the project in our list
+        if let Some(project_mut) = self.projects.get_mut(index) {
+            project_mut.target_dir = None;
+            project_mut.target_size = 0;
+            project_mut.size_capped = false;
+            project_mut.extra_artifacts.clear();
+            project_mut.extra_artifacts_size = 0;
+            project_mut.build_artifacts.clear();
+        }
+    }
+
     fn open_selected_project(&self) -> Result<()> {
         if let Some(project) = self.projects.get(self.selected_index) {
             // try to open the project directory
@@ -291,15 +1324,295 @@ impl InteractiveUI {
         Ok(())
     }
 
-    async fn refresh_projects(&mut self) -> Result<()> {
+    fn copy_selected_path(&mut self) {
+        let Some(project) = self.projects.get(self.selected_index) else {
+            return;
+        };
+
+        let path = std::fs::canonicalize(&project.path)
+            .unwrap_or_else(|_| project.path.clone())
+            .display()
+            .to_string();
+
+        if self.clipboard.is_none() {
+            self.clipboard = arboard::Clipboard::new().ok();
+        }
+
+        let copied = self
+            .clipboard
+            .as_mut()
+            .is_some_and(|clipboard| clipboard.set_text(path.clone()).is_ok());
+
+        self.status_message = Some(if copied {
+            format!("Copied to clipboard: {}", path)
+        } else {
+            format!("No clipboard available, path: {}", path)
+        });
+    }
+
+    fn start_scan(&mut self) {
         let scanner = ProjectScanner::new(self.cli.clone());
-        self.projects = scanner.scan().await?;
+        let (receiver, handle) = scanner.scan_streaming();
+        self.active_scan = Some(ActiveScan { receiver, handle });
+        self.projects.clear();
+        self.found_count = 0;
         self.selected_index = 0;
+        self.clear_pending_prompts();
+    }
+
+    /// drop a delete or retry prompt whose project may no longer be in the list
+    fn clear_pending_prompts(&mut self) {
+        let had_delete = self.pending_delete_confirmation.take().is_some();
+        let had_retry = self.pending_permission_retry.take().is_some();
+        let had_bulk = self.pending_bulk_confirmation.take().is_some();
+        if had_delete || had_retry || had_bulk {
+            self.status_message = None;
+        }
+    }
+
+    fn project_index(&self, path: &Path) -> Option<usize> {
+        self.projects.iter().position(|p| p.path == path)
+    }
+
+    /// pull newly found projects into the list, returns true once the scan has just finished
+    async fn poll_scan(&mut self) -> Result<bool> {
+        let Some(scan) = self.active_scan.as_mut() else {
+            return Ok(false);
+        };
+
+        while let Ok(project) = scan.receiver.try_recv() {
+            self.projects.push(project);
+        }
+        self.found_count = self.projects.len();
+
+        if !scan.handle.is_finished() {
+            return Ok(false);
+        }
+
+        let Some(ActiveScan { mut receiver, handle }) = self.active_scan.take() else {
+            return Ok(false);
+        };
+        while let Ok(project) = receiver.try_recv() {
+            self.projects.push(project);
+        }
+        let stats = handle.await??;
+
+        // sort the live list (which may have seen deletions meanwhile), keeping the selection
+        let selected_path = self.projects.get(self.selected_index).map(|p| p.path.clone());
+        let scanner = ProjectScanner::new(self.cli.clone());
+        scanner.sort(&mut self.projects);
+        self.found_count = self.projects.len();
+        self.projects = scanner.take_top(std::mem::take(&mut self.projects));
+        self.selected_index = selected_path
+            .and_then(|path| self.projects.iter().position(|p| p.path == path))
+            .unwrap_or(0);
+        self.scan_stats = Some(stats);
+        if self.pending_delete_confirmation.as_deref().is_some_and(|path| self.project_index(path).is_none())
+            || self.pending_permission_retry.as_deref().is_some_and(|path| self.project_index(path).is_none())
+        {
+            self.clear_pending_prompts();
+        }
+
+        Ok(true)
+    }
+
+    /// re-apply the sort settings, keeping the selected project selected
+    fn resort(&mut self) {
+        let selected_path = self.projects.get(self.selected_index).map(|p| p.path.clone());
+        ProjectScanner::new(self.cli.clone()).sort(&mut self.projects);
+        self.selected_index = selected_path
+            .and_then(|path| self.projects.iter().position(|p| p.path == path))
+            .unwrap_or(0);
+
+        let then_by = self
+            .cli
+            .then_by
+            .map(|key| format!(", then by {:?}", key).to_lowercase())
+            .unwrap_or_default();
+        let direction = if self.cli.reverse { "reversed" } else { "normal" };
+        self.status_message = Some(format!(
+            "Sorted by {}{} ({} order)",
+            format!("{:?}", self.cli.sort).to_lowercase(),
+            then_by,
+            direction
+        ));
+    }
+
+    /// with `--skip-open`, whether the project looks open in an IDE
+    fn open_in_editor(&self, project: &RustProject) -> bool {
+        self.cli.skip_open && utils::open_in_editor(&project.path, project.target_dir.as_deref())
+    }
+
+    /// protected by `.rskillignore` or with `p` during this session
+    fn is_protected(&self, project: &RustProject) -> bool {
+        project.protected
+            || (!self.session_protected.is_empty()
+                && project
+                    .path
+                    .canonicalize()
+                    .is_ok_and(|path| self.session_protected.contains(&path)))
+    }
+
+    fn toggle_compare_mark(&mut self) {
+        let Some(project) = self.projects.get(self.selected_index) else {
+            return;
+        };
+
+        if let Some(position) = self.compare_marks.iter().position(|path| path == &project.path) {
+            self.compare_marks.remove(position);
+            self.status_message = Some(format!("{} unmarked", project.display_name()));
+            return;
+        }
+
+        // marks of projects that went away in a rescan don't count
+        let projects = &self.projects;
+        self.compare_marks.retain(|path| projects.iter().any(|p| &p.path == path));
+        self.compare_marks.push(project.path.clone());
+        if self.compare_marks.len() == 2 {
+            self.show_compare = true;
+        } else {
+            self.status_message = Some(format!("{} marked, c on another project compares them", project.display_name()));
+        }
+    }
+
+    fn toggle_session_protection(&mut self) {
+        let Some(project) = self.projects.get(self.selected_index) else {
+            return;
+        };
+        if project.protected {
+            self.status_message = Some(format!("{} is already protected by .rskillignore", project.name));
+            return;
+        }
+        let Ok(path) = project.path.canonicalize() else {
+            self.status_message = Some(format!("{} no longer exists", utils::escape_path(&project.path)));
+            return;
+        };
+
+        let name = project.display_name();
+        self.status_message = Some(if self.session_protected.remove(&path) {
+            format!("{} is no longer protected", name)
+        } else {
+            self.session_protected.insert(path);
+            format!("{} is protected for this session", name)
+        });
+    }
+
+    async fn refresh_projects(&mut self) -> Result<()> {
+        self.rescanned = true;
+        self.start_scan();
+        Ok(())
+    }
+
+    async fn refresh_selected_project(&mut self) -> Result<()> {
+        if let Some(project) = self.projects.get(self.selected_index) {
+            let scanner = ProjectScanner::new(self.cli.clone());
+            match scanner.rescan_project(&project.path).await {
+                Ok(refreshed) => self.projects[self.selected_index] = refreshed,
+                // deleted or broken since the scan, it no longer belongs in the list
+                Err(e) => {
+                    self.status_message = Some(format!("{} is gone from the list: {}", project.display_name(), e));
+                    self.projects.remove(self.selected_index);
+                    self.selected_index = self.selected_index.min(self.projects.len().saturating_sub(1));
+                }
+            }
+        }
         Ok(())
     }
 }
 
+/// progress of a delete-all, which deletes one project per loop iteration
+struct BulkDeletion {
+    /// index of the next project to look at
+    next: usize,
+    /// not before this, `--throttle` after each deletion
+    resume_at: Instant,
+    denied: usize,
+    failed: usize,
+    building: usize,
+    open: usize,
+    /// deleted projects whose vendored dependencies went with them
+    vendored: usize,
+    /// projects with uncommitted git changes the user chose to leave alone
+    skip: HashSet<PathBuf>,
+    dirty: usize,
+}
+
+impl BulkDeletion {
+    fn new(skip: HashSet<PathBuf>) -> Self {
+        Self { next: 0, resume_at: Instant::now(), denied: 0, failed: 0, building: 0, open: 0, vendored: 0, skip, dirty: 0 }
+    }
+}
+
+/// a background scan whose results are still streaming in
+struct ActiveScan {
+    receiver: UnboundedReceiver<RustProject>,
+    handle: JoinHandle<error::Result<ScanStats>>,
+}
+
+/// order of the artifact list in the details pane
+#[derive(Clone, Copy)]
+enum ArtifactSort {
+    Size,
+    Type,
+    LastModified,
+}
+
+impl ArtifactSort {
+    fn next(self) -> Self {
+        match self {
+            ArtifactSort::Size => ArtifactSort::Type,
+            ArtifactSort::Type => ArtifactSort::LastModified,
+            ArtifactSort::LastModified => ArtifactSort::Size,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ArtifactSort::Size => "size",
+            ArtifactSort::Type => "type",
+            ArtifactSort::LastModified => "last modified",
+        }
+    }
+}
+
 enum ControlFlow {
     Continue,
     Exit,
+    Abort,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn test_refreshing_a_removed_project_drops_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut ui = InteractiveUI::new(Cli::parse_from(["rskill"]));
+        ui.projects = vec![
+            RustProject::fixture(dir.path().join("kept").to_str().unwrap(), 10),
+            RustProject::fixture(dir.path().join("gone").to_str().unwrap(), 20),
+        ];
+        ui.selected_index = 1;
+
+        ui.refresh_selected_project().await.unwrap();
+
+        assert_eq!(ui.projects.len(), 1);
+        assert_eq!(ui.selected_index, 0);
+        assert!(ui.status_message.as_deref().is_some_and(|m| m.contains("gone")));
+    }
+
+    #[tokio::test]
+    async fn test_ctrl_c_aborts_from_the_menu_and_overlays() {
+        let mut ui = InteractiveUI::new(Cli::parse_from(["rskill"]));
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        ui.menu_selection = Some(0);
+        assert!(matches!(ui.handle_key_event(ctrl_c).await.unwrap(), ControlFlow::Abort));
+
+        ui.menu_selection = None;
+        ui.show_help = true;
+        assert!(matches!(ui.handle_key_event(ctrl_c).await.unwrap(), ControlFlow::Abort));
+    }
 }
@@ -0,0 +1,136 @@
+use std::time::Duration;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// a byte count with an optional unit, e.g. `512`, `500M`, `500MB` or `1.5GiB`;
+/// `KiB`..`TiB` and the bare `K`..`T` (as `du -h` prints them) are binary, `KB`..`TB` are SI
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let (number, unit) = split_number(value);
+    let number: f64 = number.parse().map_err(|_| format!("invalid size `{}`", value.trim()))?;
+
+    let unit = unit.to_ascii_uppercase();
+    let multiplier: u64 = match unit.as_str() {
+        "" | "B" => 1,
+        "K" | "KIB" => 1 << 10,
+        "M" | "MIB" => 1 << 20,
+        "G" | "GIB" => 1 << 30,
+        "T" | "TIB" => 1 << 40,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        _ => return Err(format!("unknown size unit `{}`, use K, M, G or T (binary) or KB, MB, GB or TB (SI)", unit)),
+    };
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// seconds, or a number with a unit: `ms`, `s`, `m`/`min`, `h`, `d`, `w`, `mo` (30 days)
+/// or `y` (365 days), the long singular and plural forms (`2 hours`, `1 day`) work too
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let (number, unit) = split_number(value);
+    let number: u64 = number.parse().map_err(|_| format!("invalid duration `{}`", value.trim()))?;
+
+    if unit.eq_ignore_ascii_case("ms") {
+        return Ok(Duration::from_millis(number));
+    }
+    let seconds = match unit.to_ascii_lowercase().as_str() {
+        "" | "s" | "sec" | "secs" | "second" | "seconds" => 1,
+        "m" | "min" | "mins" | "minute" | "minutes" => MINUTE,
+        "h" | "hr" | "hrs" | "hour" | "hours" => HOUR,
+        "d" | "day" | "days" => DAY,
+        "w" | "week" | "weeks" => 7 * DAY,
+        "mo" | "month" | "months" => 30 * DAY,
+        "y" | "year" | "years" => 365 * DAY,
+        _ => return Err(format!("unknown duration unit `{}`, use ms, s, m, h, d, w, mo or y", unit)),
+    };
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("duration `{}` is too long", value.trim()))
+}
+
+/// whole days, a bare number is taken as days so `--older-than 30` means 30 days
+pub fn parse_days(value: &str) -> Result<i64, String> {
+    if let Ok(days) = value.trim().parse::<i64>() {
+        if days < 0 {
+            return Err(format!("days can't be negative, got `{}`", value.trim()));
+        }
+        return Ok(days);
+    }
+    Ok((parse_duration(value)?.as_secs() / DAY) as i64)
+}
+
+/// split `1.5 GiB` into `1.5` and `GiB`
+fn split_number(value: &str) -> (&str, &str) {
+    let value = value.trim();
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(unit_start);
+    (number, unit.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size_units() {
+        assert_eq!(parse_size("512"), Ok(512));
+        assert_eq!(parse_size("500M"), Ok(500 << 20));
+        assert_eq!(parse_size("5gb"), Ok(5_000_000_000));
+        assert_eq!(parse_size("1.5GiB"), Ok(3 << 29));
+        assert!(parse_size("5X").is_err());
+        assert!(parse_size("G").is_err());
+    }
+
+    #[test]
+    fn test_parse_size_binary_and_si_units() {
+        assert_eq!(parse_size("500MiB"), parse_size("500M"));
+        assert_eq!(parse_size("500MB"), Ok(500_000_000));
+        assert_eq!(parse_size("2 GiB"), Ok(2 << 30));
+        assert_eq!(parse_size("1kb"), Ok(1000));
+        assert_eq!(parse_size("1TB"), Ok(1_000_000_000_000));
+        assert_eq!(parse_size("1T"), Ok(1 << 40));
+        assert_eq!(parse_size("0.5K"), Ok(512));
+        assert!(parse_size("1PB").is_err());
+        assert!(parse_size("").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1d"), Ok(Duration::from_secs(DAY)));
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("1.5h").is_err());
+        assert!(parse_duration(&format!("{}y", u64::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration_singular_and_plural() {
+        assert_eq!(parse_duration("1 day"), parse_duration("1d"));
+        assert_eq!(parse_duration("30 days"), parse_duration("30d"));
+        assert_eq!(parse_duration("2 Hours"), parse_duration("2h"));
+        assert_eq!(parse_duration("1 minute"), parse_duration("1m"));
+        assert_eq!(parse_duration("2 weeks"), Ok(Duration::from_secs(14 * DAY)));
+        // `m` is minutes, months need `mo`
+        assert_eq!(parse_duration("6mo"), Ok(Duration::from_secs(180 * DAY)));
+        assert_eq!(parse_duration("1 year"), Ok(Duration::from_secs(365 * DAY)));
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("30"), Ok(30));
+        assert_eq!(parse_days("30d"), Ok(30));
+        assert_eq!(parse_days("2w"), Ok(14));
+        assert_eq!(parse_days("6mo"), Ok(180));
+        assert_eq!(parse_days("12h"), Ok(0));
+        assert!(parse_days("soon").is_err());
+        assert!(parse_days("-5").is_err());
+    }
+}
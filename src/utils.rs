@@ -1,19 +1,129 @@
 use std::fs;
-use anyhow::Result;
-use std::path::Path;
+use crate::error::{Result, RskillError};
+use std::path::{Component, Path, PathBuf};
 use walkdir::WalkDir;
+use crate::cli::{PathStyle, TimestampFormat};
+use crate::project::LargestFile;
+use chrono::{DateTime, SecondsFormat, Utc};
 
 /// calculate the total size of a directory
-pub fn calculate_dir_size(dir: &Path) -> Result<u64> {
+///
+/// by default this sums the apparent (logical) file sizes; with `disk_usage`
+/// set it sums the blocks actually allocated on disk, like `du` does, which is
+/// what gets reclaimed on compressed or large-block filesystems
+///
+/// symlinks are not followed or counted, so dangling ones are harmless, and files
+/// that vanish or can't be read mid-walk are skipped rather than failing the total
+pub fn calculate_dir_size(dir: &Path, disk_usage: bool) -> Result<u64> {
+    Ok(calculate_dir_size_capped(dir, disk_usage, None)?.0)
+}
+
+/// like `calculate_dir_size`, but the walk stops as soon as the total passes `cap`,
+/// the size is then reported as the cap itself along with `true`
+pub fn calculate_dir_size_capped(dir: &Path, disk_usage: bool, cap: Option<u64>) -> Result<(u64, bool)> {
+    walk_dir_size(dir, disk_usage, cap, None)
+}
+
+/// like `calculate_dir_size_capped`, also finding the largest file within the same walk
+/// (among the files seen before the cap stopped it)
+pub fn calculate_dir_size_with_largest(
+    dir: &Path,
+    disk_usage: bool,
+    cap: Option<u64>,
+) -> Result<(u64, bool, Option<LargestFile>)> {
+    let mut largest = None;
+    let (size, capped) = walk_dir_size(dir, disk_usage, cap, Some(&mut largest))?;
+    Ok((size, capped, largest))
+}
+
+fn walk_dir_size(
+    dir: &Path,
+    disk_usage: bool,
+    cap: Option<u64>,
+    mut largest: Option<&mut Option<LargestFile>>,
+) -> Result<(u64, bool)> {
     let mut total_size = 0u64;
     
     for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
         if entry.file_type().is_file() {
-            total_size += entry.metadata()?.len();
+            if let Ok(metadata) = entry.metadata() {
+                let size = file_size(&metadata, disk_usage);
+                total_size += size;
+                if let Some(largest) = largest.as_deref_mut() {
+                    if largest.as_ref().is_none_or(|l| size > l.size) {
+                        *largest = Some(LargestFile { path: entry.into_path(), size });
+                    }
+                }
+            }
+        }
+        if let Some(cap) = cap.filter(|&cap| total_size > cap) {
+            return Ok((cap, true));
         }
     }
     
-    Ok(total_size)
+    Ok((total_size, false))
+}
+
+/// whether a directory holds nothing but (possibly nested) empty directories
+pub fn is_empty_dir_tree(dir: &Path) -> bool {
+    WalkDir::new(dir)
+        .into_iter()
+        .all(|entry| entry.is_ok_and(|e| e.file_type().is_dir()))
+}
+
+/// total size of the filesystem a path lives on, as reported by `df`
+#[cfg(unix)]
+pub fn disk_capacity(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_df_capacity(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(unix))]
+pub fn disk_capacity(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// the size column of POSIX `df -Pk` output, in bytes
+fn parse_df_capacity(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    // the device name may contain spaces, the numbers are counted from the end
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let blocks = fields.iter().rev().nth(4)?.parse::<u64>().ok()?;
+    Some(blocks * 1024)
+}
+
+/// id of the filesystem a path lives on, `None` where that isn't available
+#[cfg(unix)]
+pub fn device_id(path: &Path) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+
+    fs::metadata(path).ok().map(|m| m.dev())
+}
+
+#[cfg(not(unix))]
+pub fn device_id(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// size of a single file, either apparent or allocated on disk
+#[cfg(unix)]
+pub fn file_size(metadata: &fs::Metadata, disk_usage: bool) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+
+    if disk_usage {
+        metadata.blocks() * 512
+    } else {
+        metadata.len()
+    }
+}
+
+/// size of a single file, allocated size is not available so always apparent
+#[cfg(not(unix))]
+pub fn file_size(metadata: &fs::Metadata, _disk_usage: bool) -> u64 {
+    metadata.len()
 }
 
 /// format bytes as human readable size
@@ -25,6 +135,56 @@ pub fn format_size(bytes: u64, use_gb: bool) -> String {
     }
 }
 
+/// a count shortened for prose, e.g. `950`, `45K` or `2.3M`
+pub fn format_count(count: u64) -> String {
+    match count {
+        0..=9_999 => count.to_string(),
+        10_000..=999_999 => format!("{}K", count / 1_000),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
+
+/// `part` as a percentage of `whole`, `-` when the whole is unknown or empty
+pub fn format_percent(part: u64, whole: Option<u64>) -> String {
+    match whole {
+        Some(whole) if whole > 0 => format!("{:.1}%", part as f64 * 100.0 / whole as f64),
+        _ => "-".to_string(),
+    }
+}
+
+/// a bar of block characters, `width` cells long at `max`, drawn to an eighth of a cell
+pub fn size_bar(size: u64, max: u64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+    if max == 0 {
+        return " ".repeat(width);
+    }
+    let eighths = (size.min(max) as u128 * width as u128 * 8 / max as u128) as usize;
+    let (full, partial) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if partial > 0 {
+        bar.push(PARTIAL[partial]);
+    }
+    format!("{:<width$}", bar)
+}
+
+/// format a last-modified timestamp
+pub fn format_timestamp(timestamp: Option<DateTime<Utc>>, format: &TimestampFormat) -> String {
+    let Some(dt) = timestamp else {
+        return "Unknown".to_string();
+    };
+
+    match format {
+        TimestampFormat::Iso => dt.to_rfc3339_opts(SecondsFormat::Secs, true),
+        TimestampFormat::Date => dt.format("%Y-%m-%d").to_string(),
+        TimestampFormat::Relative => match (Utc::now() - dt).num_days() {
+            0 => "Today".to_string(),
+            1 => "1 day ago".to_string(),
+            days => format!("{} days ago", days),
+        },
+    }
+}
+
 /// safely remove a directory and its contents
 pub fn remove_directory(path: &Path, dry_run: bool) -> Result<()> {
     if dry_run {
@@ -35,25 +195,330 @@ pub fn remove_directory(path: &Path, dry_run: bool) -> Result<()> {
     }
     
     if path.exists() {
-        fs::remove_dir_all(path)?;
+        fs::remove_dir_all(path).map_err(|source| match source.kind() {
+            std::io::ErrorKind::PermissionDenied => RskillError::PermissionDenied { path: path.to_path_buf() },
+            _ => RskillError::DeleteFailed { path: path.to_path_buf(), source },
+        })?;
     }
     
     Ok(())
 }
 
+/// run a whitespace-separated command in a directory with the output going straight
+/// to the terminal, returns whether it succeeded
+pub fn run_command_in(dir: &Path, command: &str) -> Result<bool> {
+    let mut parts = command.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "empty command").into());
+    };
+
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .current_dir(dir)
+        .status()?;
+    Ok(status.success())
+}
+
+/// run a `--pre-delete-cmd`/`--post-delete-cmd` hook for a project, split on whitespace
+/// like `rebuild_command`, with the project path and size in bytes appended as arguments
+/// and set as `RSKILL_PROJECT_PATH`/`RSKILL_SIZE`; without a command, or in a dry run,
+/// nothing runs and the hook counts as successful
+pub fn run_delete_hook(command: Option<&str>, dry_run: bool, project_dir: &Path, size: u64) -> Result<bool> {
+    let Some(command) = command.filter(|_| !dry_run) else {
+        return Ok(true);
+    };
+    let mut parts = command.split_whitespace();
+    let Some(program) = parts.next() else {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "empty command").into());
+    };
+
+    let status = std::process::Command::new(program)
+        .args(parts)
+        .arg(project_dir)
+        .arg(size.to_string())
+        .env("RSKILL_PROJECT_PATH", project_dir)
+        .env("RSKILL_SIZE", size.to_string())
+        .stdin(std::process::Stdio::null())
+        .status()?;
+    Ok(status.success())
+}
+
+/// remove a directory through `sudo`, lets the user authenticate on the terminal
+#[cfg(unix)]
+pub fn remove_directory_with_sudo(path: &Path) -> Result<()> {
+    let status = std::process::Command::new("sudo")
+        .arg("rm")
+        .arg("-rf")
+        .arg("--")
+        .arg(path)
+        .status()?;
+
+    if !status.success() {
+        return Err(RskillError::CommandFailed { command: "sudo rm".to_string(), status });
+    }
+
+    Ok(())
+}
+
+/// rustup's home, `RUSTUP_HOME` or `~/.rustup`
+pub fn rustup_home() -> Option<PathBuf> {
+    home_from_env("RUSTUP_HOME", ".rustup")
+}
+
+/// cargo's home, `CARGO_HOME` or `~/.cargo`
+pub fn cargo_home() -> Option<PathBuf> {
+    home_from_env("CARGO_HOME", ".cargo")
+}
+
+/// rskill's own config, cache and data directories, never scanned or cleaned
+pub fn rskill_dirs() -> Vec<PathBuf> {
+    let mut own_dirs: Vec<_> = [dirs::config_dir(), dirs::cache_dir(), dirs::data_dir()]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join("rskill"))
+        .collect();
+    own_dirs.dedup();
+    own_dirs
+}
+
+/// a directory relocated by an environment variable, an empty value counts as unset
+fn home_from_env(var: &str, default_dir: &str) -> Option<PathBuf> {
+    std::env::var_os(var)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(default_dir)))
+}
+
+/// name of the default rustup toolchain, `None` when rustup isn't available
+pub fn default_rustup_toolchain() -> Option<String> {
+    let output = std::process::Command::new("rustup").arg("default").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+/// uninstall a toolchain through rustup so its bookkeeping stays consistent,
+/// falling back to deleting the directory when rustup can't be run
+pub fn remove_rustup_toolchain(path: &Path, dry_run: bool) -> Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    if dry_run {
+        println!(" [DRY RUN] Would uninstall toolchain: {}", name);
+        return Ok(());
+    }
+
+    match std::process::Command::new("rustup")
+        .args(["toolchain", "uninstall"])
+        .arg(name.as_ref())
+        .status()
+    {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(RskillError::CommandFailed {
+            command: "rustup toolchain uninstall".to_string(),
+            status,
+        }),
+        Err(_) => {
+            eprintln!("Warning: rustup not found, deleting {} directly", path.display());
+            remove_directory(path, false)
+        }
+    }
+}
+
 /// check if a path is a git repository
-pub fn _is_git_repo(path: &Path) -> bool {
-    path.join(".git").exists()
+pub fn is_git_repo(path: &Path) -> bool {
+    git_checkout_root(path).is_some()
+}
+
+/// the working tree a path is checked out in, the nearest directory holding a `.git`
+pub fn git_checkout_root(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|dir| dir.join(".git").exists())
+}
+
+/// the main working tree (canonical) of the repository a path belongs to; for a linked worktree
+/// (a `.git` file pointing into `<repo>/.git/worktrees/<name>`) that's the originating
+/// repository, otherwise the checkout itself
+pub fn git_repository(path: &Path) -> Option<PathBuf> {
+    let checkout = git_checkout_root(path)?;
+    let dot_git = checkout.join(".git");
+    if dot_git.is_dir() {
+        return checkout.canonicalize().ok();
+    }
+
+    // submodules have a `.git` file too, only worktrees have a `commondir`
+    let content = fs::read_to_string(&dot_git).ok()?;
+    let git_dir = checkout.join(content.strip_prefix("gitdir:")?.trim());
+    let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => return checkout.canonicalize().ok(),
+    };
+
+    let common_dir = common_dir.canonicalize().ok()?;
+    match common_dir.file_name() {
+        Some(name) if name == ".git" => common_dir.parent().map(Path::to_path_buf),
+        // a bare repository has no main working tree, it's named by its git dir
+        _ => Some(common_dir),
+    }
+}
+
+/// whether git reports uncommitted or untracked files in the project, not counting
+/// its target directory, `None` when git isn't available or the project isn't tracked
+pub fn has_uncommitted_changes(project_dir: &Path, target_dir: Option<&Path>) -> Option<bool> {
+    let mut command = std::process::Command::new("git");
+    command.arg("-C").arg(project_dir).args(["status", "--porcelain", "--", "."]);
+    if let Some(target) = target_dir.and_then(|dir| dir.strip_prefix(project_dir).ok()) {
+        command.arg(format!(":(exclude){}", target.display()));
+    }
+
+    let output = command.output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+    Some(!output.stdout.is_empty())
+}
+
+/// whether cargo currently holds one of the build locks (`<profile>/.cargo-lock`) in the
+/// target directory, meaning a build is running against it
+pub fn build_in_progress(target_dir: &Path) -> bool {
+    WalkDir::new(target_dir)
+        .max_depth(3)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.file_name() == ".cargo-lock")
+        .any(|e| {
+            fs::File::open(e.path())
+                .is_ok_and(|file| matches!(file.try_lock(), Err(fs::TryLockError::WouldBlock)))
+        })
+}
+
+/// take cargo's package cache lock (`$CARGO_HOME/.package-cache`) for as long as the
+/// returned file lives, `None` while cargo holds it to download or extract crates
+pub fn lock_package_cache(cargo_dir: &Path) -> Result<Option<fs::File>> {
+    let file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(cargo_dir.join(".package-cache"))?;
+    match file.try_lock() {
+        Ok(()) => Ok(Some(file)),
+        Err(fs::TryLockError::WouldBlock) => Ok(None),
+        Err(fs::TryLockError::Error(err)) => Err(err.into()),
+    }
 }
 
-/// get relative path from current working directory
-pub fn get_relative_path(path: &Path) -> String {
-    if let Ok(current_dir) = std::env::current_dir() {
-        if let Ok(relative) = path.strip_prefix(&current_dir) {
-            return relative.display().to_string();
+/// how recently an editor must have written to a project for it to count as open
+const EDITOR_ACTIVITY_WINDOW: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// heuristic for a project being open in an IDE: an editor's workspace state or
+/// rust-analyzer's own target directory was written to within the last hour; a
+/// committed `.vscode` or `.idea` alone doesn't count
+pub fn open_in_editor(project_dir: &Path, target_dir: Option<&Path>) -> bool {
+    let mut candidates = vec![
+        project_dir.join(".idea/workspace.xml"),
+        project_dir.join(".vscode"),
+        project_dir.join(".zed"),
+    ];
+    candidates.extend(target_dir.map(|target| target.join("rust-analyzer")));
+
+    candidates.iter().any(|path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age < EDITOR_ACTIVITY_WINDOW))
+    })
+}
+
+/// a path the way `--paths` asks for it, escaped for the terminal
+pub fn display_path(path: &Path, style: &PathStyle) -> String {
+    escape_path(&styled_path(path, style))
+}
+
+/// a path the way `--paths` asks for it, relative paths are taken from the working directory
+pub fn styled_path(path: &Path, style: &PathStyle) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let Ok(current_dir) = std::env::current_dir() else {
+        return absolute;
+    };
+
+    match style {
+        PathStyle::Auto if absolute.starts_with(&current_dir) => relative_path(&absolute, &current_dir),
+        PathStyle::Relative => relative_path(&absolute, &current_dir),
+        PathStyle::Auto | PathStyle::Absolute => absolute,
+    }
+}
+
+/// a path with its control characters escaped, so a directory named with a newline or an
+/// escape sequence can't break a table line or the terminal
+pub fn escape_path(path: &Path) -> String {
+    escape_control(&path.display().to_string())
+}
+
+/// `text` as a single shell word, single-quoted unless it's plain enough to paste as is
+pub fn shell_quote(text: &str) -> String {
+    let plain = !text.is_empty()
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/' | '+' | '@'));
+    if plain {
+        text.to_string()
+    } else {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+/// `\n`, `\u{1b}` and the like instead of the raw control characters
+pub fn escape_control(text: &str) -> String {
+    if !text.chars().any(char::is_control) {
+        return text.to_string();
+    }
+    text.chars()
+        .map(|c| if c.is_control() { c.escape_default().to_string() } else { c.to_string() })
+        .collect()
+}
+
+/// `path` relative to `base`, both absolute
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let common = path
+        .components()
+        .zip(base.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative: PathBuf = base.components().skip(common).map(|_| Component::ParentDir).collect();
+    relative.extend(path.components().skip(common));
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+/// `path` made absolute with `.` and `..` resolved lexically, symlinks are left alone
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
         }
     }
-    path.display().to_string()
+    normalized
+}
+
+/// check if the current working directory is inside the given project
+pub fn contains_current_dir(path: &Path) -> bool {
+    let (Ok(current_dir), Ok(path)) = (
+        std::env::current_dir().and_then(fs::canonicalize),
+        fs::canonicalize(path),
+    ) else {
+        return false;
+    };
+
+    current_dir.starts_with(path)
 }
 
 /// check if the current directory contains important system files
@@ -75,13 +540,32 @@ pub fn _is_system_directory(path: &Path) -> bool {
     important_files.iter().any(|&important| path_str.contains(important))
 }
 
-/// truncate a string to a maximum length with ellipsis
+/// minimal `*` wildcard matching for a single path component
+pub fn matches_wildcard(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            text.starts_with(prefix)
+                && (0..=text.len() - prefix.len()).any(|i| {
+                    text.is_char_boundary(prefix.len() + i)
+                        && matches_wildcard(rest, &text[prefix.len() + i..])
+                })
+        }
+    }
+}
+
+/// truncate a string to a maximum length with an ellipsis in the middle
 pub fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max_len.saturating_sub(3)])
+    let len = s.chars().count();
+    if len <= max_len {
+        return s.to_string();
     }
+
+    // keep both ends, for paths the leading components matter as much as the name
+    let kept = max_len.saturating_sub(3);
+    let head: String = s.chars().take(kept / 2).collect();
+    let tail: String = s.chars().skip(len - (kept - kept / 2)).collect();
+    format!("{}...{}", head, tail)
 }
 
 #[cfg(test)]
@@ -90,16 +574,67 @@ mod tests {
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_format_count_and_shell_quote() {
+        assert_eq!(format_count(950), "950");
+        assert_eq!(format_count(45_300), "45K");
+        assert_eq!(format_count(2_310_000), "2.3M");
+        assert_eq!(shell_quote("node_modules"), "node_modules");
+        assert_eq!(shell_quote("my stuff"), "'my stuff'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_package_cache_lock_is_exclusive() {
+        let dir = tempdir().unwrap();
+        let held = lock_package_cache(dir.path()).unwrap();
+        assert!(held.is_some());
+        assert!(lock_package_cache(dir.path()).unwrap().is_none());
+        drop(held);
+        assert!(lock_package_cache(dir.path()).unwrap().is_some());
+    }
+
     #[test]
     fn test_format_size() {
         assert_eq!(format_size(1024 * 1024, false), "1.00 MB");
         assert_eq!(format_size(1024 * 1024 * 1024, true), "1.00 GB");
     }
 
+    #[test]
+    fn test_format_timestamp() {
+        let dt = DateTime::parse_from_rfc3339("2024-06-01T14:32:10Z").unwrap().with_timezone(&Utc);
+        assert_eq!(format_timestamp(Some(dt), &TimestampFormat::Iso), "2024-06-01T14:32:10Z");
+        assert_eq!(format_timestamp(Some(dt), &TimestampFormat::Date), "2024-06-01");
+        assert_eq!(format_timestamp(None, &TimestampFormat::Iso), "Unknown");
+    }
+
     #[test]
     fn test_truncate_string() {
         assert_eq!(truncate_string("hello", 10), "hello");
-        assert_eq!(truncate_string("hello world", 8), "hello...");
+        assert_eq!(truncate_string("hello world", 8), "he...rld");
+        assert_eq!(truncate_string("/home/me/projects/app", 12), "/hom...s/app");
+    }
+
+    #[test]
+    fn test_size_bar() {
+        assert_eq!(size_bar(100, 100, 4), "████");
+        assert_eq!(size_bar(50, 100, 4), "██  ");
+        assert_eq!(size_bar(1, 16, 2), "▏ ");
+        assert_eq!(size_bar(0, 0, 3), "   ");
+    }
+
+    #[test]
+    fn test_escape_control() {
+        assert_eq!(escape_control("plain/path"), "plain/path");
+        assert_eq!(escape_control("evil\nname\u{1b}[2J"), "evil\\nname\\u{1b}[2J");
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let base = Path::new("/home/me/work");
+        assert_eq!(relative_path(Path::new("/home/me/work/app"), base), Path::new("app"));
+        assert_eq!(relative_path(Path::new("/home/me/other/app"), base), Path::new("../other/app"));
+        assert_eq!(relative_path(base, base), Path::new("."));
     }
 
     #[test]
@@ -108,9 +643,214 @@ mod tests {
         let file_path = temp_dir.path().join("test.txt");
         fs::write(&file_path, "Hello, world!")?;
         
-        let size = calculate_dir_size(temp_dir.path())?;
+        let size = calculate_dir_size(temp_dir.path(), false)?;
         assert!(size > 0);
         
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_delete_hook_gets_path_and_size() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir()?;
+        let project = temp_dir.path().join("app");
+        let hook = temp_dir.path().join("hook.sh");
+        fs::write(
+            &hook,
+            "#!/bin/sh\n[ \"$1\" = \"$RSKILL_PROJECT_PATH\" ] && [ \"$2\" = 42 ] && [ \"$RSKILL_SIZE\" = 42 ]\n",
+        )?;
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+
+        assert!(run_delete_hook(hook.to_str(), false, &project, 42)?);
+        assert!(!run_delete_hook(hook.to_str(), false, &project, 7)?);
+        assert!(!run_delete_hook(Some("false"), false, &project, 42)?);
+        assert!(run_delete_hook(Some("false"), true, &project, 42)?);
+        assert!(run_delete_hook(None, false, &project, 42)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_percent() {
+        assert_eq!(format_percent(15, Some(100)), "15.0%");
+        assert_eq!(format_percent(1, Some(3)), "33.3%");
+        assert_eq!(format_percent(5, Some(0)), "-");
+        assert_eq!(format_percent(5, None), "-");
+    }
+
+    #[test]
+    fn test_parse_df_capacity() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/nvme0n1p2   488245288 200000000 288245288      41% /home\n";
+        assert_eq!(parse_df_capacity(output), Some(488245288 * 1024));
+        assert_eq!(parse_df_capacity("Filesystem 1024-blocks Used Available Capacity Mounted on\n"), None);
+    }
+
+    #[test]
+    fn test_open_in_editor_needs_recent_activity() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let target = temp_dir.path().join("target");
+        assert!(!open_in_editor(temp_dir.path(), Some(&target)));
+
+        fs::create_dir(temp_dir.path().join(".idea"))?;
+        let workspace = fs::File::create(temp_dir.path().join(".idea/workspace.xml"))?;
+        assert!(open_in_editor(temp_dir.path(), Some(&target)));
+
+        workspace.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(2 * 60 * 60))?;
+        assert!(!open_in_editor(temp_dir.path(), Some(&target)));
+
+        fs::create_dir_all(target.join("rust-analyzer"))?;
+        assert!(open_in_editor(temp_dir.path(), Some(&target)));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_build_in_progress_follows_cargo_lock() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let profile_dir = temp_dir.path().join("debug");
+        fs::create_dir(&profile_dir)?;
+        let lock = fs::File::create(profile_dir.join(".cargo-lock"))?;
+
+        assert!(!build_in_progress(temp_dir.path()));
+        lock.lock()?;
+        assert!(build_in_progress(temp_dir.path()));
+        lock.unlock()?;
+        assert!(!build_in_progress(temp_dir.path()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_dir_size_stops_at_cap() -> Result<()> {
+        let temp_dir = tempdir()?;
+        for i in 0..4 {
+            fs::write(temp_dir.path().join(format!("{}.bin", i)), vec![0u8; 100])?;
+        }
+
+        assert_eq!(calculate_dir_size_capped(temp_dir.path(), false, Some(250))?, (250, true));
+        assert_eq!(calculate_dir_size_capped(temp_dir.path(), false, Some(400))?, (400, false));
+        assert_eq!(calculate_dir_size_capped(temp_dir.path(), false, None)?, (400, false));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_calculate_dir_size_finds_largest_file() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir(temp_dir.path().join("deps"))?;
+        fs::write(temp_dir.path().join("small.bin"), vec![0u8; 100])?;
+        fs::write(temp_dir.path().join("deps/big.bin"), vec![0u8; 300])?;
+
+        let (size, capped, largest) = calculate_dir_size_with_largest(temp_dir.path(), false, None)?;
+        assert_eq!((size, capped), (400, false));
+        let largest = largest.unwrap();
+        assert_eq!(largest.path, temp_dir.path().join("deps/big.bin"));
+        assert_eq!(largest.size, 300);
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_calculate_dir_size_skips_broken_symlinks() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::write(temp_dir.path().join("real.bin"), "12345")?;
+        std::os::unix::fs::symlink(temp_dir.path().join("missing"), temp_dir.path().join("dangling"))?;
+        
+        assert_eq!(calculate_dir_size(temp_dir.path(), false)?, 5);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_empty_dir_tree() -> Result<()> {
+        let temp_dir = tempdir()?;
+        fs::create_dir_all(temp_dir.path().join("debug/deps"))?;
+        assert!(is_empty_dir_tree(temp_dir.path()));
+
+        fs::write(temp_dir.path().join("debug/.cargo-lock"), "")?;
+        assert!(!is_empty_dir_tree(temp_dir.path()));
+        
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_calculate_dir_size_disk_usage() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let file_path = temp_dir.path().join("test.txt");
+        fs::write(&file_path, "Hello, world!")?;
+        
+        let apparent = calculate_dir_size(temp_dir.path(), false)?;
+        let allocated = calculate_dir_size(temp_dir.path(), true)?;
+        assert_eq!(apparent, 13);
+        assert_eq!(allocated % 512, 0);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_uncommitted_changes_ignore_target() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let project = temp_dir.path();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(project)
+                .args(args)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        };
+        if !git(&["init", "-q"]) {
+            // git isn't installed, nothing to check
+            return Ok(());
+        }
+
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"demo\"\n")?;
+        assert!(git(&["add", "."]));
+        assert!(git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-qm", "init"]));
+
+        let target = project.join("target");
+        fs::create_dir(&target)?;
+        fs::write(target.join("build.bin"), "x")?;
+        assert!(is_git_repo(project));
+        assert_eq!(has_uncommitted_changes(project, Some(&target)), Some(false));
+
+        fs::write(project.join("Cargo.toml"), "[package]\nname = \"changed\"\n")?;
+        assert_eq!(has_uncommitted_changes(project, Some(&target)), Some(true));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_repository_resolves_worktrees() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let main = temp_dir.path().canonicalize()?.join("main");
+        let worktree_git_dir = main.join(".git/worktrees/feature");
+        fs::create_dir_all(&worktree_git_dir)?;
+        fs::write(worktree_git_dir.join("commondir"), "../..\n")?;
+
+        let worktree = temp_dir.path().join("feature");
+        fs::create_dir_all(worktree.join("crates/app"))?;
+        fs::write(worktree.join(".git"), format!("gitdir: {}\n", worktree_git_dir.display()))?;
+
+        // a submodule's git dir has no commondir
+        let submodule = main.join("vendor/lib");
+        fs::create_dir_all(main.join(".git/modules/lib"))?;
+        fs::create_dir_all(&submodule)?;
+        fs::write(submodule.join(".git"), "gitdir: ../../.git/modules/lib\n")?;
+
+        assert_eq!(git_repository(&main.join("src")), Some(main.clone()));
+        assert_eq!(git_repository(&worktree.join("crates/app")), Some(main));
+        assert_eq!(git_checkout_root(&worktree.join("crates/app")), Some(worktree.as_path()));
+        assert_eq!(git_repository(&submodule), Some(submodule));
+        assert_eq!(git_repository(temp_dir.path()), None);
+
+        Ok(())
+    }
 }